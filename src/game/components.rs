@@ -10,12 +10,110 @@ pub struct Player;
 #[derive(Component, Reflect)]
 pub struct Opponent;
 
+/// A point of interest shown on the map
+#[derive(Component, Reflect, Default)]
+pub struct Objective;
+
 #[derive(Component, Reflect)]
 pub struct Health {
     pub current: u32,
     pub max: u32,
 }
 
+/// Stamina spent on sprinting, jumping and dodging
+#[derive(Component, Reflect)]
+pub struct Stamina {
+    pub current: f32,
+    pub max: f32,
+    /// Stamina regained per second
+    pub regen_rate: f32,
+    /// Seconds to wait after spending stamina before it starts regenerating
+    pub regen_delay: f32,
+    /// Fraction of `max` that must be regained before an exhausted entity recovers
+    pub recovery_threshold: f32,
+    /// Movement multiplier applied while exhausted
+    pub exhausted_speed_multiplier: f32,
+    /// Set when stamina runs out, cleared once `recovery_threshold` is reached
+    pub exhausted: bool,
+    /// Seconds since stamina was last spent
+    pub since_spent: f32,
+}
+
+impl Stamina {
+    pub fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            ..default()
+        }
+    }
+
+    /// Whether `amount` can be spent right now
+    pub fn can_afford(&self, amount: f32) -> bool {
+        !self.exhausted && self.current >= amount
+    }
+
+    /// Spends `amount` if it can be afforded, returning whether it was spent
+    pub fn try_spend(&mut self, amount: f32) -> bool {
+        if !self.can_afford(amount) {
+            return false;
+        }
+        self.drain(amount);
+        true
+    }
+
+    /// Spends up to `amount`, becoming exhausted when stamina runs out
+    pub fn drain(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.0);
+        self.since_spent = 0.0;
+        if self.current <= 0.0 {
+            self.exhausted = true;
+        }
+    }
+
+    /// Regenerates stamina after the regeneration delay has passed
+    pub fn regenerate(&mut self, delta: f32) {
+        self.since_spent += delta;
+        if self.since_spent < self.regen_delay {
+            return;
+        }
+
+        // Exhaustion halves the regeneration rate until recovered
+        let rate = if self.exhausted { self.regen_rate * 0.5 } else { self.regen_rate };
+        self.current = (self.current + rate * delta).min(self.max);
+        if self.exhausted && self.current >= self.max * self.recovery_threshold {
+            self.exhausted = false;
+        }
+    }
+
+    /// The movement multiplier from exhaustion
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.exhausted { self.exhausted_speed_multiplier } else { 1.0 }
+    }
+}
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self {
+            current: 100.0,
+            max: 100.0,
+            regen_rate: 20.0,
+            regen_delay: 1.0,
+            recovery_threshold: 0.3,
+            exhausted_speed_multiplier: 0.6,
+            exhausted: false,
+            since_spent: 0.0,
+        }
+    }
+}
+
+/// Marks an entity that can't take damage for the remaining seconds
+#[derive(Component, Reflect)]
+#[component(storage = "SparseSet")]
+pub struct Invulnerable {
+    pub remaining: f32,
+}
+
 /******************************************************************************
  *                                   INPUT                                    *
  ******************************************************************************/
@@ -23,10 +121,25 @@ pub struct Health {
 #[derive(Component, Default)]
 pub struct PlayerInput;
 
+/// How movement input is turned into a direction in the world.
+#[derive(Component, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlScheme {
+    /// Sideways input turns the character, forward input moves it along its facing.
+    #[default]
+    Tank,
+    /// Input is relative to the camera's yaw and the character turns to face
+    /// the direction it moves in.
+    CameraRelative,
+}
+
 /******************************************************************************
  *                              UI MARKER COMPONENTS                          *
  ******************************************************************************/
 
+/// Marks the root of gameplay HUD elements, hidden in photo mode
+#[derive(Component, Default)]
+pub struct Hud;
+
 // #[derive(Component)]
 // pub struct MainMenuButton;
 
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+/******************************************************************************
+ *                              CORE GAME EVENTS                              *
+ ******************************************************************************/
+
+/// Sent whenever an entity's [`Stamina`](super::components::Stamina) changes
+#[derive(Event, Debug, Clone, Copy)]
+pub struct StaminaChanged {
+    pub entity: Entity,
+    pub current: f32,
+    pub max: f32,
+    pub exhausted: bool,
+}
+
+/// Sent when an entity starts a dash, for VFX and audio hooks
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Dashed {
+    pub entity: Entity,
+    pub direction: Vec3,
+}
+
+/// Sent when an entity starts an attack
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AttackStarted {
+    pub entity: Entity,
+}
+
+/// Sent by animations when a character's foot touches the ground
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Footstep {
+    pub entity: Entity,
+}
+
+/// Sent by attack animations on the frame the hit connects
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HitFrame {
+    pub entity: Entity,
+}
+
+/// Adds trauma to every camera with a
+/// [`CameraShake`](crate::rendering::cameras::camera_shake::CameraShake).
+/// An intensity of 1 is the strongest possible shake.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ShakeCamera {
+    pub intensity: f32,
+}
+
+/// What caused a [`PlayerDamaged`] event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageSource {
+    Fall,
+}
+
+/// Sent when the player loses health
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlayerDamaged {
+    pub entity: Entity,
+    pub amount: u32,
+    pub source: DamageSource,
+}
@@ -1,4 +1,7 @@
-use bevy::prelude::*;
+use bevy::{platform::collections::HashSet, prelude::*};
+
+use crate::rendering::cameras::camera_mode::CameraMode;
+use crate::ControlScheme;
 
 /******************************************************************************
  *                             CORE GAME RESOURCES                            *
@@ -6,3 +9,63 @@ use bevy::prelude::*;
 
 #[derive(Resource, Default)]
 pub struct MainTrack;
+
+/// Statistics of the current run, shown on the game over screen
+#[derive(Resource, Default, Debug)]
+pub struct RunSummary {
+    /// Seconds spent in gameplay
+    pub time_survived: f32,
+    pub damage_taken: u32,
+    /// Landings hard enough to hurt
+    pub hard_landings: u32,
+}
+
+/// Something that can hold gameplay time paused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PauseReason {
+    PauseMenu,
+    PhotoMode,
+}
+
+/// Everything currently holding gameplay and physics time paused. Time only
+/// runs again once every reason is gone, so resuming one doesn't undo another.
+#[derive(Resource, Default, Debug)]
+pub struct PausedBy(HashSet<PauseReason>);
+
+impl PausedBy {
+    pub fn pause(&mut self, reason: PauseReason) {
+        self.0.insert(reason);
+    }
+
+    pub fn resume(&mut self, reason: PauseReason) {
+        self.0.remove(&reason);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        !self.0.is_empty()
+    }
+
+    /// Drops every reason, when the run they belong to ends
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Player preferences edited on the settings screen
+#[derive(Resource, Debug)]
+pub struct GameSettings {
+    pub control_scheme: ControlScheme,
+    pub camera_mode: CameraMode,
+    /// Accessibility option, turning this off disables all camera shake
+    pub camera_shake: bool,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            control_scheme: ControlScheme::default(),
+            camera_mode: CameraMode::default(),
+            camera_shake: true,
+        }
+    }
+}
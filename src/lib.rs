@@ -12,7 +12,8 @@ pub mod rendering;
 pub mod ui;
 
 pub use game::components::*;
-pub use game::resources::MainTrack;
+pub use game::events::*;
+pub use game::resources::{GameSettings, MainTrack};
 pub use starknet::NetworkingPlugin;
 pub use systems::input::ElysiumInputPlugin;
 
@@ -21,11 +22,14 @@ pub struct GamePlugin;
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, setup_camera)
+            .init_resource::<GameSettings>()
             .add_plugins(EnhancedInputPlugin)
             .add_plugins(AudioPlugin)
             .add_plugins(ElysiumInputPlugin)
             .add_plugins(NetworkingPlugin)
             .add_plugins(UiLunexPlugins)
+            .add_plugins(rendering::cameras::camera_shake::CameraShakePlugin)
+            .add_plugins(rendering::cameras::cinematic::CinematicPlugin)
             .add_plugins(screens::plugin)
             .add_plugins(systems::plugin);
     }
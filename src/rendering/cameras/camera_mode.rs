@@ -0,0 +1,233 @@
+use bevy::{
+    input::mouse::MouseMotion,
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+use super::player_camera::{CameraRig, FlyCam};
+use crate::{GameSettings, Player};
+
+/// How the gameplay camera frames the action
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraMode {
+    /// Orbiting follow camera behind the player
+    #[default]
+    ThirdPerson,
+    /// Isometric view from a fixed pitch that pans when the cursor reaches the screen edge
+    TopDown,
+    /// Detached camera flying freely, for debugging
+    FreeDebug,
+}
+
+impl CameraMode {
+    /// The mode after this one when cycling with input
+    pub fn next(self) -> Self {
+        match self {
+            CameraMode::ThirdPerson => CameraMode::TopDown,
+            CameraMode::TopDown if cfg!(debug_assertions) => CameraMode::FreeDebug,
+            CameraMode::TopDown | CameraMode::FreeDebug => CameraMode::ThirdPerson,
+        }
+    }
+}
+
+/// Settings and pan state of the top-down camera
+#[derive(Resource)]
+pub struct TopDownCamera {
+    pub pitch: f32,
+    pub yaw: f32,
+    pub distance: f32,
+    /// Distance from the window edge in pixels where panning starts
+    pub edge_margin: f32,
+    pub pan_speed: f32,
+    /// How far the view can be panned away from the player
+    pub max_pan: f32,
+    pan: Vec3,
+}
+
+impl Default for TopDownCamera {
+    fn default() -> Self {
+        Self {
+            pitch: 55.0_f32.to_radians(),
+            yaw: 45.0_f32.to_radians(),
+            distance: 45.0,
+            edge_margin: 20.0,
+            pan_speed: 30.0,
+            max_pan: 40.0,
+            pan: Vec3::ZERO,
+        }
+    }
+}
+
+/// Settings and look state of the free debug camera
+#[derive(Resource)]
+pub struct FreeCamera {
+    pub speed: f32,
+    pub boost: f32,
+    pub mouse_sensitivity: f32,
+    yaw: f32,
+    pitch: f32,
+}
+
+impl Default for FreeCamera {
+    fn default() -> Self {
+        Self {
+            speed: 30.0,
+            boost: 3.0,
+            mouse_sensitivity: 0.005,
+            yaw: 0.0,
+            pitch: 0.0,
+        }
+    }
+}
+
+/// Blends a camera from the pose it had when the mode changed to its new rig
+#[derive(Component)]
+pub struct CameraTransition {
+    from: Transform,
+    elapsed: f32,
+    pub duration: f32,
+}
+
+impl CameraTransition {
+    const DURATION: f32 = 0.8;
+
+    /// Eases a camera from `from` back onto its rig
+    pub fn from_pose(from: Transform) -> Self {
+        Self {
+            from,
+            elapsed: 0.0,
+            duration: Self::DURATION,
+        }
+    }
+}
+
+/// Applies the camera mode chosen in the settings.
+pub(super) fn sync_camera_mode(settings: Res<GameSettings>, mut mode: ResMut<CameraMode>) {
+    mode.set_if_neq(settings.camera_mode);
+}
+
+/// Starts a transition and resets per-mode state whenever the camera mode changes.
+pub(super) fn start_camera_transition(
+    mut commands: Commands,
+    mode: Res<CameraMode>,
+    mut top_down: ResMut<TopDownCamera>,
+    mut free_camera: ResMut<FreeCamera>,
+    cameras: Query<(Entity, &Transform), With<FlyCam>>,
+) {
+    for (entity, transform) in &cameras {
+        match *mode {
+            CameraMode::TopDown => top_down.pan = Vec3::ZERO,
+            CameraMode::FreeDebug => {
+                // Keep looking the same way the previous mode did
+                let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+                free_camera.yaw = yaw;
+                free_camera.pitch = pitch;
+            }
+            CameraMode::ThirdPerson => {}
+        }
+
+        commands.entity(entity).insert(CameraTransition::from_pose(*transform));
+    }
+}
+
+/// Frames the player from a fixed isometric angle, panning at the screen edges.
+pub(super) fn update_top_down_camera(
+    time: Res<Time>,
+    mut top_down: ResMut<TopDownCamera>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    players: Query<&Transform, With<Player>>,
+    mut cameras: Query<&mut CameraRig, With<FlyCam>>,
+) {
+    let Ok(player) = players.single() else {
+        return;
+    };
+
+    let rotation = Quat::from_euler(EulerRot::YXZ, top_down.yaw, -top_down.pitch, 0.0);
+
+    // Pan along the ground in the camera's screen directions
+    if let Some((cursor, size)) = windows
+        .single()
+        .ok()
+        .and_then(|window| Some((window.cursor_position()?, window.size())))
+    {
+        let margin = top_down.edge_margin;
+        let edge = Vec2::new(
+            (cursor.x > size.x - margin) as i8 as f32 - (cursor.x < margin) as i8 as f32,
+            (cursor.y < margin) as i8 as f32 - (cursor.y > size.y - margin) as i8 as f32,
+        );
+        let right = (rotation * Vec3::X).with_y(0.0).normalize_or_zero();
+        let forward = (rotation * Vec3::NEG_Z).with_y(0.0).normalize_or_zero();
+        let pan = top_down.pan
+            + (right * edge.x + forward * edge.y) * top_down.pan_speed * time.delta_secs();
+        top_down.pan = pan.clamp_length_max(top_down.max_pan);
+    }
+
+    let focus = player.translation + top_down.pan;
+    for mut rig in &mut cameras {
+        rig.rotation = rotation;
+        rig.translation = focus - rotation * Vec3::NEG_Z * top_down.distance;
+    }
+}
+
+/// Flies the debug camera with WASD, Q/E for height and the mouse while right-click is held.
+pub(super) fn update_free_camera(
+    // Real time, so the camera keeps flying while the game is paused
+    time: Res<Time<Real>>,
+    mut free_camera: ResMut<FreeCamera>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    mut cameras: Query<&mut CameraRig, With<FlyCam>>,
+) {
+    let mouse_delta: Vec2 = mouse_motion_events.read().map(|e| e.delta).sum();
+    if mouse_input.pressed(MouseButton::Right) {
+        free_camera.yaw -= mouse_delta.x * free_camera.mouse_sensitivity;
+        free_camera.pitch = (free_camera.pitch - mouse_delta.y * free_camera.mouse_sensitivity)
+            .clamp(-89_f32.to_radians(), 89_f32.to_radians());
+    }
+
+    let axis = |positive: KeyCode, negative: KeyCode| {
+        keyboard_input.pressed(positive) as i8 as f32 - keyboard_input.pressed(negative) as i8 as f32
+    };
+    let input = Vec3::new(
+        axis(KeyCode::KeyD, KeyCode::KeyA),
+        axis(KeyCode::KeyE, KeyCode::KeyQ),
+        axis(KeyCode::KeyS, KeyCode::KeyW),
+    );
+    let speed = if keyboard_input.pressed(KeyCode::ShiftLeft) {
+        free_camera.speed * free_camera.boost
+    } else {
+        free_camera.speed
+    };
+
+    let rotation = Quat::from_euler(EulerRot::YXZ, free_camera.yaw, free_camera.pitch, 0.0);
+    for mut rig in &mut cameras {
+        rig.rotation = rotation;
+        rig.translation += rotation * input.normalize_or_zero() * speed * time.delta_secs();
+    }
+}
+
+/// Moves cameras onto their rig, easing from the old pose while a transition runs.
+pub(super) fn apply_camera_rig(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut cameras: Query<(Entity, &CameraRig, &mut Transform, Option<&mut CameraTransition>)>,
+) {
+    for (entity, rig, mut transform, transition) in &mut cameras {
+        let Some(mut transition) = transition else {
+            transform.translation = rig.translation;
+            transform.rotation = rig.rotation;
+            continue;
+        };
+
+        transition.elapsed += time.delta_secs();
+        let progress = (transition.elapsed / transition.duration).min(1.0);
+        let t = EaseFunction::CubicInOut.sample_clamped(progress);
+        transform.translation = transition.from.translation.lerp(rig.translation, t);
+        transform.rotation = transition.from.rotation.slerp(rig.rotation, t);
+
+        if progress >= 1.0 {
+            commands.entity(entity).remove::<CameraTransition>();
+        }
+    }
+}
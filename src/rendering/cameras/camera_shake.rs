@@ -0,0 +1,150 @@
+use bevy::{prelude::*, transform::TransformSystem};
+
+use crate::{GameSettings, ShakeCamera};
+
+/// Shakes a camera based on accumulated trauma.
+///
+/// Trauma is added through [`ShakeCamera`] events and decays over time. The
+/// shake scales with trauma squared, so small hits stay subtle while big ones
+/// are violent. Works on top of any camera controller, as the offset is
+/// removed again before the controllers run each frame.
+#[derive(Component, Debug, Clone)]
+pub struct CameraShake {
+    /// Current trauma between 0 and 1
+    pub trauma: f32,
+    /// Trauma lost per second
+    pub decay: f32,
+    /// Largest translation offset at full trauma
+    pub max_offset: f32,
+    /// Largest yaw and pitch offset at full trauma, in radians
+    pub max_angle: f32,
+    /// Largest roll offset at full trauma, in radians
+    pub max_roll: f32,
+    /// How fast the noise driving the shake changes
+    pub frequency: f32,
+    time: f32,
+    applied: Transform,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            trauma: 0.0,
+            decay: 1.2,
+            max_offset: 0.5,
+            max_angle: 3.0_f32.to_radians(),
+            max_roll: 5.0_f32.to_radians(),
+            frequency: 15.0,
+            time: 0.0,
+            applied: Transform::IDENTITY,
+        }
+    }
+}
+
+impl CameraShake {
+    /// A shake scaled for cameras framing something small up close
+    pub fn subtle() -> Self {
+        Self {
+            max_offset: 0.02,
+            max_angle: 1.0_f32.to_radians(),
+            max_roll: 1.5_f32.to_radians(),
+            ..default()
+        }
+    }
+
+    /// Adds trauma, saturating at 1
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+}
+
+/// Smooth 1D gradient noise in roughly `-1..1`
+fn noise(seed: u32, x: f32) -> f32 {
+    let gradient = |cell: i32| {
+        let mut hash = (cell as u32).wrapping_mul(0x27d4_eb2d) ^ seed.wrapping_mul(0x1656_67b1);
+        hash ^= hash >> 15;
+        hash = hash.wrapping_mul(0x85eb_ca6b);
+        hash ^= hash >> 13;
+        (hash as f32 / u32::MAX as f32) * 2.0 - 1.0
+    };
+
+    let cell = x.floor();
+    let t = x - cell;
+    let cell = cell as i32;
+    let a = gradient(cell) * t;
+    let b = gradient(cell + 1) * (t - 1.0);
+    // Quintic fade, as in Perlin noise
+    let fade = t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+    a.lerp(b, fade) * 2.0
+}
+
+/// Removes last frame's shake so camera controllers see their own transform.
+fn remove_shake(mut cameras: Query<(&mut CameraShake, &mut Transform)>) {
+    for (mut shake, mut transform) in &mut cameras {
+        let applied = std::mem::take(&mut shake.applied);
+        transform.rotation *= applied.rotation.inverse();
+        transform.translation -= transform.rotation * applied.translation;
+    }
+}
+
+/// Adds trauma to every shaking camera, unless shake is turned off in the settings.
+fn add_trauma(
+    mut shake_event_reader: EventReader<ShakeCamera>,
+    settings: Res<GameSettings>,
+    mut cameras: Query<&mut CameraShake>,
+) {
+    let trauma: f32 = shake_event_reader.read().map(|event| event.intensity).sum();
+    if !settings.camera_shake {
+        for mut shake in &mut cameras {
+            shake.trauma = 0.0;
+        }
+        return;
+    }
+    if trauma > 0.0 {
+        for mut shake in &mut cameras {
+            shake.add_trauma(trauma);
+        }
+    }
+}
+
+/// Offsets shaking cameras after their controllers have placed them.
+pub(super) fn apply_shake(time: Res<Time>, mut cameras: Query<(&mut CameraShake, &mut Transform)>) {
+    for (mut shake, mut transform) in &mut cameras {
+        shake.trauma = (shake.trauma - shake.decay * time.delta_secs()).max(0.0);
+        if shake.trauma <= 0.0 {
+            continue;
+        }
+
+        shake.time += time.delta_secs() * shake.frequency;
+        let amount = shake.trauma * shake.trauma;
+        let t = shake.time;
+
+        let offset = Vec3::new(noise(0, t), noise(1, t), noise(2, t)) * shake.max_offset * amount;
+        let rotation = Quat::from_euler(
+            EulerRot::YXZ,
+            noise(3, t) * shake.max_angle * amount,
+            noise(4, t) * shake.max_angle * amount,
+            noise(5, t) * shake.max_roll * amount,
+        );
+
+        // The offset is in camera space, so it reads the same from every angle
+        transform.translation += transform.rotation * offset;
+        transform.rotation *= rotation;
+        shake.applied = Transform::from_translation(offset).with_rotation(rotation);
+    }
+}
+
+pub struct CameraShakePlugin;
+
+impl Plugin for CameraShakePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShakeCamera>()
+            .add_systems(PreUpdate, remove_shake)
+            .add_systems(
+                PostUpdate,
+                (add_trauma, apply_shake)
+                    .chain()
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
@@ -0,0 +1,438 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+    sprite::Anchor,
+    transform::TransformSystem,
+};
+use bevy_lunex::*;
+use serde::Deserialize;
+
+use super::camera_mode::CameraTransition;
+use super::camera_shake::apply_shake;
+use super::player_camera::CameraRig;
+
+/// A camera pose at a point in time of a [`CinematicSequence`]
+#[derive(Debug, Clone, Copy)]
+pub struct CinematicKeyframe {
+    /// Seconds since the start of the sequence
+    pub time: f32,
+    pub position: Vec3,
+    /// Point the camera looks at
+    pub look_at: Vec3,
+    /// Vertical field of view in radians, or `None` to keep the camera's own
+    pub fov: Option<f32>,
+}
+
+/// A named cue fired partway through a [`CinematicSequence`]
+#[derive(Debug, Clone)]
+pub struct CinematicCue {
+    pub time: f32,
+    pub name: String,
+}
+
+/// A scripted camera move, played by adding a [`CinematicPlayback`] to a camera.
+///
+/// The camera follows a Catmull-Rom spline through the keyframe positions
+/// while looking along a spline through their look-at targets. Sequences are
+/// built in code or loaded from `.cinematic.ron` files, see [`PlayCinematic`].
+#[derive(Asset, TypePath, Debug, Clone, Default)]
+pub struct CinematicSequence {
+    keyframes: Vec<CinematicKeyframe>,
+    cues: Vec<CinematicCue>,
+    /// Index of the keyframe added last, for [`Self::with_fov`]
+    last_added: Option<usize>,
+    /// Show black bars at the top and bottom of the screen while playing
+    pub letterbox: bool,
+}
+
+impl CinematicSequence {
+    pub fn new() -> Self {
+        Self {
+            letterbox: true,
+            ..default()
+        }
+    }
+
+    /// Adds a keyframe, keeping the keyframes sorted by time
+    pub fn with_keyframe(mut self, time: f32, position: Vec3, look_at: Vec3) -> Self {
+        let keyframe = CinematicKeyframe {
+            time,
+            position,
+            look_at,
+            fov: None,
+        };
+        let index = self.keyframes.partition_point(|k| k.time <= time);
+        self.keyframes.insert(index, keyframe);
+        self.last_added = Some(index);
+        self
+    }
+
+    /// Sets the field of view of the last added keyframe, in degrees
+    pub fn with_fov(mut self, fov: f32) -> Self {
+        if let Some(keyframe) = self.last_added.and_then(|index| self.keyframes.get_mut(index)) {
+            keyframe.fov = Some(fov.to_radians());
+        }
+        self
+    }
+
+    /// Fires a [`CinematicCueReached`] on the camera at `time`
+    pub fn with_cue(mut self, time: f32, name: impl Into<String>) -> Self {
+        self.cues.push(CinematicCue {
+            time,
+            name: name.into(),
+        });
+        self
+    }
+
+    pub fn with_letterbox(mut self, letterbox: bool) -> Self {
+        self.letterbox = letterbox;
+        self
+    }
+
+    /// Length of the sequence in seconds
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    /// The camera position, look-at target and field of view at `time`
+    fn sample(&self, time: f32) -> Option<(Vec3, Vec3, Option<f32>)> {
+        let last = self.keyframes.len().checked_sub(1)?;
+        if last == 0 {
+            let keyframe = &self.keyframes[0];
+            return Some((keyframe.position, keyframe.look_at, keyframe.fov));
+        }
+        let next = self.keyframes.partition_point(|k| k.time <= time).clamp(1, last);
+        let current = next - 1;
+
+        let (a, b) = (&self.keyframes[current], &self.keyframes[next]);
+        let span = b.time - a.time;
+        let t = if span > 0.0 { ((time - a.time) / span).clamp(0.0, 1.0) } else { 1.0 };
+
+        // Neighbours for the spline tangents, mirrored at the ends
+        let before = current.checked_sub(1).map(|i| &self.keyframes[i]);
+        let after = self.keyframes.get(next + 1);
+        let spline = |get: fn(&CinematicKeyframe) -> Vec3| {
+            let p1 = get(a);
+            let p2 = get(b);
+            let p0 = before.map_or(2.0 * p1 - p2, get);
+            let p3 = after.map_or(2.0 * p2 - p1, get);
+            catmull_rom(p0, p1, p2, p3, t)
+        };
+
+        let fov = match (a.fov, b.fov) {
+            (Some(from), Some(to)) => Some(from.lerp(to, EaseFunction::SmoothStep.sample_clamped(t))),
+            (from, to) => from.or(to),
+        };
+        Some((spline(|k| k.position), spline(|k| k.look_at), fov))
+    }
+}
+
+/// The layout of a `.cinematic.ron` file
+#[derive(Deserialize)]
+struct CinematicFile {
+    #[serde(default = "default_letterbox")]
+    letterbox: bool,
+    keyframes: Vec<KeyframeFile>,
+    #[serde(default)]
+    cues: Vec<CueFile>,
+}
+
+#[derive(Deserialize)]
+struct KeyframeFile {
+    time: f32,
+    position: [f32; 3],
+    look_at: [f32; 3],
+    /// Vertical field of view in degrees
+    #[serde(default)]
+    fov: Option<f32>,
+}
+
+#[derive(Deserialize)]
+struct CueFile {
+    time: f32,
+    name: String,
+}
+
+fn default_letterbox() -> bool {
+    true
+}
+
+impl From<CinematicFile> for CinematicSequence {
+    fn from(file: CinematicFile) -> Self {
+        let mut sequence = CinematicSequence::new().with_letterbox(file.letterbox);
+        for keyframe in file.keyframes {
+            sequence = sequence.with_keyframe(
+                keyframe.time,
+                keyframe.position.into(),
+                keyframe.look_at.into(),
+            );
+            if let Some(fov) = keyframe.fov {
+                sequence = sequence.with_fov(fov);
+            }
+        }
+        for cue in file.cues {
+            sequence = sequence.with_cue(cue.time, cue.name);
+        }
+        sequence
+    }
+}
+
+/// Loads [`CinematicSequence`]s from `.cinematic.ron` files
+#[derive(Default)]
+struct CinematicSequenceLoader;
+
+impl AssetLoader for CinematicSequenceLoader {
+    type Asset = CinematicSequence;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: CinematicFile = ron::de::from_bytes(&bytes)?;
+        Ok(file.into())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["cinematic.ron"]
+    }
+}
+
+fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+/// Plays a [`CinematicSequence`] on the camera it's added to, removing itself when done
+#[derive(Component)]
+pub struct CinematicPlayback {
+    sequence: CinematicSequence,
+    elapsed: f32,
+    next_cue: usize,
+    skipped: bool,
+    original_fov: Option<f32>,
+    letterbox: Option<Entity>,
+    /// The bars of this playback's letterbox
+    bars: Vec<Entity>,
+}
+
+impl CinematicPlayback {
+    pub fn play(mut sequence: CinematicSequence) -> Self {
+        sequence.cues.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self {
+            sequence,
+            elapsed: 0.0,
+            next_cue: 0,
+            skipped: false,
+            original_fov: None,
+            letterbox: None,
+            bars: Vec::new(),
+        }
+    }
+
+    /// Jumps to the end of the sequence. Remaining cues still fire, so
+    /// scripted changes to the world aren't lost.
+    pub fn skip(&mut self) {
+        self.skipped = true;
+    }
+}
+
+/// Plays a [`CinematicSequence`] asset on the camera once it has loaded
+#[derive(Component, Debug, Clone)]
+pub struct PlayCinematic(pub Handle<CinematicSequence>);
+
+/// Triggered on the camera when a [`CinematicCue`] is reached
+#[derive(Event, Debug, Clone)]
+pub struct CinematicCueReached {
+    pub name: String,
+}
+
+/// Triggered on the camera when a [`CinematicPlayback`] ends
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CinematicEnded {
+    pub skipped: bool,
+}
+
+/// One of the black bars shown during a letterboxed cinematic
+#[derive(Component)]
+struct LetterboxBar {
+    top: bool,
+}
+
+impl LetterboxBar {
+    /// Bar height in percent of the screen at full coverage
+    const HEIGHT: f32 = 12.0;
+    /// Seconds the bars take to slide in and out
+    const SLIDE: f32 = 0.5;
+
+    fn layout(&self, coverage: f32) -> UiLayout {
+        let layout = UiLayout::window().width(Rl(100.0)).height(Rl(Self::HEIGHT * coverage));
+        match self.top {
+            true => layout.pack(),
+            false => layout.y(Rl(100.0)).anchor(Anchor::BottomLeft).pack(),
+        }
+    }
+}
+
+/// Starts the playback of sequence assets that finished loading.
+fn play_loaded_cinematics(
+    mut commands: Commands,
+    sequences: Res<Assets<CinematicSequence>>,
+    cameras: Query<(Entity, &PlayCinematic)>,
+) {
+    for (entity, play) in &cameras {
+        if let Some(sequence) = sequences.get(&play.0) {
+            commands
+                .entity(entity)
+                .remove::<PlayCinematic>()
+                .insert(CinematicPlayback::play(sequence.clone()));
+        }
+    }
+}
+
+/// Spawns the letterbox and remembers the camera's field of view.
+fn start_cinematics(
+    mut commands: Commands,
+    mut cameras: Query<(&mut CinematicPlayback, Option<&Projection>), Added<CinematicPlayback>>,
+) {
+    for (mut playback, projection) in &mut cameras {
+        if let Some(Projection::Perspective(perspective)) = projection {
+            playback.original_fov = Some(perspective.fov);
+        }
+        if !playback.sequence.letterbox {
+            continue;
+        }
+
+        let mut bars = Vec::new();
+        let letterbox = commands
+            .spawn((Name::new("Letterbox"), UiLayoutRoot::new_2d(), UiFetchFromCamera::<0>))
+            .with_children(|ui| {
+                for top in [true, false] {
+                    let bar = LetterboxBar { top };
+                    let entity = ui
+                        .spawn((
+                            bar.layout(0.0),
+                            Sprite::default(),
+                            UiColor::new(vec![(UiBase::id(), Color::BLACK)]),
+                            Pickable::IGNORE,
+                            bar,
+                        ))
+                        .id();
+                    bars.push(entity);
+                }
+            })
+            .id();
+        playback.letterbox = Some(letterbox);
+        playback.bars = bars;
+    }
+}
+
+/// Moves cinematic cameras along their sequence and fires cues and completion events.
+fn play_cinematics(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cameras: Query<(
+        Entity,
+        &mut CinematicPlayback,
+        &mut Transform,
+        Option<&mut Projection>,
+        Has<CameraRig>,
+    )>,
+    mut bars: Query<(&LetterboxBar, &mut UiLayout)>,
+) {
+    for (entity, mut playback, mut transform, mut projection, has_rig) in &mut cameras {
+        let duration = playback.sequence.duration();
+        playback.elapsed = match playback.skipped {
+            true => duration,
+            false => playback.elapsed + time.delta_secs(),
+        };
+        let elapsed = playback.elapsed.min(duration);
+
+        if let Some((position, look_at, fov)) = playback.sequence.sample(elapsed) {
+            *transform = Transform::from_translation(position).looking_at(look_at, Vec3::Y);
+            if let (Some(fov), Some(Projection::Perspective(perspective))) =
+                (fov, projection.as_deref_mut())
+            {
+                perspective.fov = fov;
+            }
+        }
+
+        while let Some(cue) = playback.sequence.cues.get(playback.next_cue) {
+            if cue.time > elapsed {
+                break;
+            }
+            commands.trigger_targets(CinematicCueReached { name: cue.name.clone() }, entity);
+            playback.next_cue += 1;
+        }
+
+        let coverage = (elapsed / LetterboxBar::SLIDE)
+            .min((duration - elapsed) / LetterboxBar::SLIDE)
+            .clamp(0.0, 1.0);
+        let mut bar_iter = bars.iter_many_mut(&playback.bars);
+        while let Some((bar, mut layout)) = bar_iter.fetch_next() {
+            *layout = bar.layout(EaseFunction::CubicOut.sample_clamped(coverage));
+        }
+
+        if elapsed < duration {
+            continue;
+        }
+
+        // Hand the camera back to its controller
+        if let (Some(fov), Some(Projection::Perspective(perspective))) =
+            (playback.original_fov, projection.as_deref_mut())
+        {
+            perspective.fov = fov;
+        }
+        let mut camera = commands.entity(entity);
+        camera.remove::<CinematicPlayback>();
+        if has_rig {
+            camera.insert(CameraTransition::from_pose(*transform));
+        }
+        commands.trigger_targets(
+            CinematicEnded {
+                skipped: playback.skipped,
+            },
+            entity,
+        );
+    }
+}
+
+/// Removes the letterbox with the playback, also when the camera is despawned mid-sequence.
+fn remove_letterbox(
+    trigger: Trigger<OnRemove, CinematicPlayback>,
+    mut commands: Commands,
+    playbacks: Query<&CinematicPlayback>,
+) {
+    if let Some(letterbox) = playbacks.get(trigger.target()).ok().and_then(|p| p.letterbox) {
+        commands.entity(letterbox).try_despawn();
+    }
+}
+
+pub struct CinematicPlugin;
+
+impl Plugin for CinematicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<CinematicSequence>()
+            .init_asset_loader::<CinematicSequenceLoader>()
+            .add_event::<CinematicCueReached>()
+            .add_event::<CinematicEnded>()
+            .add_observer(remove_letterbox)
+            .add_systems(
+                PostUpdate,
+                (play_loaded_cinematics, start_cinematics, play_cinematics)
+                    .chain()
+                    // Shake still plays on top of the scripted move
+                    .before(apply_shake)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
@@ -0,0 +1,381 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    platform::collections::HashSet,
+    prelude::*,
+    render::{
+        camera::ScalingMode,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        view::RenderLayers,
+    },
+    sprite::Anchor,
+};
+use bevy_lunex::*;
+
+use crate::ui::styles::ElysiumDescentColorPalette;
+use crate::{Hud, Objective, Opponent, Player};
+
+/// Render layer with the map markers and fog, only seen by the map camera
+pub const MINIMAP_LAYER: usize = 3;
+
+/// Whether the map is shown as a HUD corner or over the whole screen
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapView {
+    #[default]
+    Minimap,
+    FullScreen,
+}
+
+impl MapView {
+    pub fn toggled(self) -> Self {
+        match self {
+            MapView::Minimap => MapView::FullScreen,
+            MapView::FullScreen => MapView::Minimap,
+        }
+    }
+
+    fn layout(self) -> UiLayout {
+        match self {
+            MapView::Minimap => UiLayout::window()
+                .pos(Rl((97.0, 4.0)))
+                .anchor(Anchor::TopRight)
+                .size(Rh(28.0))
+                .pack(),
+            MapView::FullScreen => UiLayout::window().full().pack(),
+        }
+    }
+}
+
+/// A top-down orthographic camera that renders the map around the player
+#[derive(Component)]
+pub struct MinimapCamera {
+    /// Height of the camera above the player
+    pub height: f32,
+    /// Half the width of the area shown on the minimap
+    pub minimap_radius: f32,
+    /// Half the width of the area shown on the full screen map
+    pub world_map_radius: f32,
+    radius: f32,
+}
+
+impl Default for MinimapCamera {
+    fn default() -> Self {
+        Self {
+            height: 200.0,
+            minimap_radius: 40.0,
+            world_map_radius: 150.0,
+            radius: 40.0,
+        }
+    }
+}
+
+/// The parts of the world the player has walked near, in square cells
+#[derive(Resource)]
+pub struct ExploredAreas {
+    pub cell_size: f32,
+    /// Distance around the player that counts as explored
+    pub reveal_radius: f32,
+    cells: HashSet<IVec2>,
+}
+
+impl Default for ExploredAreas {
+    fn default() -> Self {
+        Self {
+            cell_size: 10.0,
+            reveal_radius: 25.0,
+            cells: HashSet::default(),
+        }
+    }
+}
+
+impl ExploredAreas {
+    fn cell(&self, position: Vec3) -> IVec2 {
+        (position.xz() / self.cell_size).floor().as_ivec2()
+    }
+
+    pub fn is_explored(&self, position: Vec3) -> bool {
+        self.cells.contains(&self.cell(position))
+    }
+}
+
+/// Gizmos drawn only on the map
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct MinimapGizmos;
+
+/// The sprite showing the map render texture
+#[derive(Component)]
+struct MinimapView;
+
+/// A plane under the map camera darkening unexplored cells, one pixel per cell
+#[derive(Component, Default)]
+struct MapFog {
+    mask: Option<Handle<Image>>,
+    /// The cell in the middle of the mask when it was last drawn
+    center: Option<IVec2>,
+}
+
+impl MapFog {
+    const COLOR: [u8; 4] = [0, 0, 0, 217];
+}
+
+/// Spawns the map camera rendering to a texture and the HUD element showing it.
+/// Both get `scene` so they despawn together with the rest of the scene.
+pub fn spawn_minimap(commands: &mut Commands, images: &mut Assets<Image>, scene: impl Bundle + Clone) {
+    commands.insert_resource(ExploredAreas::default());
+    commands.insert_resource(MapView::default());
+
+    // Create the transparent render texture
+    let image_handle = images.add(Image::clear_render_texture());
+
+    // Spawn the camera looking straight down, with north at the top
+    let camera = MinimapCamera::default();
+    commands.spawn((
+        Name::new("Minimap Camera"),
+        Camera3d::default(),
+        Camera::clear_render_to(image_handle.clone()).with_order(-1),
+        Projection::from(OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical {
+                viewport_height: camera.radius * 2.0,
+            },
+            ..OrthographicProjection::default_3d()
+        }),
+        Transform::from_xyz(0.0, camera.height, 0.0).looking_at(Vec3::ZERO, Vec3::NEG_Z),
+        RenderLayers::from_layers(&[0, MINIMAP_LAYER]),
+        camera,
+        scene.clone(),
+    ));
+
+    commands.spawn((
+        Name::new("Map Fog"),
+        MapFog::default(),
+        Transform::default(),
+        Visibility::default(),
+        scene.clone(),
+    ));
+
+    // Create UI
+    commands.spawn((
+        UiLayoutRoot::new_2d(),
+        // Make the UI synchronized with camera viewport size
+        UiFetchFromCamera::<0>,
+        Hud,
+        scene,
+    )).with_children(|ui| {
+
+        // Spawn the map plane
+        ui.spawn((
+            Name::new("Minimap"),
+            MapView::Minimap.layout(),
+            Sprite::from_image(image_handle),
+            UiEmbedding,
+            MinimapView,
+            Pickable::IGNORE,
+        ));
+    });
+}
+
+/// Keeps the map camera above the player and zoomed for the current view.
+fn follow_player(
+    map_view: Res<MapView>,
+    players: Query<&GlobalTransform, With<Player>>,
+    mut cameras: Query<(&mut MinimapCamera, &mut Transform, &mut Projection)>,
+) {
+    let Ok(player) = players.single() else {
+        return;
+    };
+    let player = player.translation();
+
+    for (mut camera, mut transform, mut projection) in &mut cameras {
+        camera.radius = match *map_view {
+            MapView::Minimap => camera.minimap_radius,
+            MapView::FullScreen => camera.world_map_radius,
+        };
+        transform.translation = player + Vec3::Y * camera.height;
+        if let Projection::Orthographic(orthographic) = projection.as_mut() {
+            orthographic.scaling_mode = ScalingMode::FixedVertical {
+                viewport_height: camera.radius * 2.0,
+            };
+        }
+    }
+}
+
+/// Resizes the map between the HUD corner and full screen.
+fn update_map_view(map_view: Res<MapView>, mut views: Query<&mut UiLayout, With<MinimapView>>) {
+    for mut layout in &mut views {
+        *layout = map_view.layout();
+    }
+}
+
+/// Marks the cells around the player as explored.
+fn explore(mut explored: ResMut<ExploredAreas>, players: Query<&GlobalTransform, With<Player>>) {
+    for player in &players {
+        let center = player.translation();
+        let reach = (explored.reveal_radius / explored.cell_size).ceil() as i32;
+        let origin = explored.cell(center);
+        for x in -reach..=reach {
+            for y in -reach..=reach {
+                let cell = origin + IVec2::new(x, y);
+                let cell_center = (cell.as_vec2() + 0.5) * explored.cell_size;
+                // Only insert new cells, so the fog is redrawn when something was revealed
+                if cell_center.distance(center.xz()) <= explored.reveal_radius
+                    && !explored.cells.contains(&cell)
+                {
+                    explored.cells.insert(cell);
+                }
+            }
+        }
+    }
+}
+
+/// Redraws the fog mask around the map camera when it moves to another cell
+/// or new cells are explored. The fog is only seen by the map camera.
+fn update_fog(
+    mut commands: Commands,
+    explored: Res<ExploredAreas>,
+    cameras: Query<(&MinimapCamera, &Transform), Without<MapFog>>,
+    mut fogs: Query<(Entity, &mut MapFog, &mut Transform)>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let (Ok((camera, camera_transform)), Ok((fog_entity, mut fog, mut fog_transform))) =
+        (cameras.single(), fogs.single_mut())
+    else {
+        return;
+    };
+
+    // The mask covers the full screen map, with the camera's cell in the middle
+    let reach = (camera.world_map_radius / explored.cell_size).ceil() as i32;
+    let size = (reach * 2 + 1) as u32;
+    let center = explored.cell(camera_transform.translation);
+    let center_position = (center.as_vec2() + 0.5) * explored.cell_size;
+
+    // Float just under the camera so the fog covers everything below it
+    fog_transform.translation = Vec3::new(
+        center_position.x,
+        camera_transform.translation.y - 1.0,
+        center_position.y,
+    );
+
+    let mask = fog.mask.get_or_insert_with(|| {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &MapFog::COLOR,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        // Hard cell edges, like the explored grid
+        image.sampler = ImageSampler::nearest();
+        let image = images.add(image);
+
+        commands.entity(fog_entity).insert((
+            Mesh3d(meshes.add(Plane3d::new(
+                Vec3::Y,
+                Vec2::splat(size as f32 * explored.cell_size * 0.5),
+            ))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color_texture: Some(image.clone()),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            })),
+            RenderLayers::layer(MINIMAP_LAYER),
+        ));
+        image
+    })
+    .clone();
+
+    if fog.center == Some(center) && !explored.is_changed() {
+        return;
+    }
+    let Some(data) = images.get_mut(&mask).and_then(|image| image.data.as_mut()) else {
+        return;
+    };
+
+    // Texture rows run along +Z and columns along +X of the plane
+    for (index, pixel) in data.chunks_exact_mut(4).enumerate() {
+        let offset = IVec2::new(index as i32 % size as i32, index as i32 / size as i32);
+        let cell = center + offset - IVec2::splat(reach);
+        let alpha = if explored.cells.contains(&cell) { 0 } else { MapFog::COLOR[3] };
+        pixel.copy_from_slice(&[MapFog::COLOR[0], MapFog::COLOR[1], MapFog::COLOR[2], alpha]);
+    }
+    fog.center = Some(center);
+}
+
+/// Draws the player, enemies and objectives on the map.
+fn draw_markers(
+    mut gizmos: Gizmos<MinimapGizmos>,
+    cameras: Query<(&MinimapCamera, &Transform)>,
+    players: Query<&GlobalTransform, With<Player>>,
+    opponents: Query<&GlobalTransform, With<Opponent>>,
+    objectives: Query<&GlobalTransform, With<Objective>>,
+) {
+    let Ok((camera, camera_transform)) = cameras.single() else {
+        return;
+    };
+    // Keep markers the same size on screen at any zoom, between the camera and the fog
+    let size = camera.radius * 0.04;
+    let height = camera_transform.translation.y - 0.5;
+    let flat = Quat::from_rotation_arc(Vec3::Z, Vec3::Y);
+    let at = |transform: &GlobalTransform| transform.translation().with_y(height);
+
+    for transform in &objectives {
+        gizmos.rect(
+            Isometry3d::new(at(transform), flat * Quat::from_rotation_z(45_f32.to_radians())),
+            Vec2::splat(size * 1.5),
+            Color::ELYSIUM_DESCENT_YELLOW,
+        );
+    }
+
+    for transform in &opponents {
+        gizmos.circle(Isometry3d::new(at(transform), flat), size, Color::ELYSIUM_DESCENT_RED);
+    }
+
+    for transform in &players {
+        // An arrow pointing where the model faces
+        let facing = transform.back().with_y(0.0).normalize_or_zero();
+        let side = facing.cross(Vec3::Y);
+        let tip = at(transform) + facing * size * 1.5;
+        let base = at(transform) - facing * size;
+        gizmos.line(tip, base + side * size, Color::ELYSIUM_DESCENT_BLUE);
+        gizmos.line(tip, base - side * size, Color::ELYSIUM_DESCENT_BLUE);
+        gizmos.line(base + side * size, base - side * size, Color::ELYSIUM_DESCENT_BLUE);
+    }
+}
+
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MapView>()
+            .init_resource::<ExploredAreas>()
+            .init_gizmo_group::<MinimapGizmos>()
+            .insert_gizmo_config(
+                MinimapGizmos,
+                GizmoConfig {
+                    render_layers: RenderLayers::layer(MINIMAP_LAYER),
+                    line: GizmoLineConfig {
+                        width: 4.0,
+                        ..default()
+                    },
+                    ..default()
+                },
+            )
+            .add_systems(
+                Update,
+                (
+                    follow_player,
+                    update_map_view.run_if(resource_changed::<MapView>),
+                    explore,
+                    update_fog,
+                    draw_markers,
+                )
+                    .chain(),
+            );
+    }
+}
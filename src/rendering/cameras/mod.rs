@@ -1,2 +1,7 @@
+pub mod camera_mode;
+pub mod camera_shake;
+pub mod cinematic;
+pub mod minimap;
+pub mod photo_mode;
 pub mod player_camera;
 pub mod showcase;
@@ -0,0 +1,375 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::{
+    core_pipeline::dof::{DepthOfField, DepthOfFieldMode},
+    input::mouse::MouseWheel,
+    prelude::*,
+    render::{
+        camera::Exposure,
+        view::screenshot::{Screenshot, ScreenshotCaptured, save_to_disk},
+    },
+    sprite::Anchor,
+};
+use bevy_lunex::*;
+
+use super::camera_mode::CameraMode;
+use super::player_camera::FlyCam;
+use crate::Hud;
+use crate::game::resources::{PauseReason, PausedBy};
+use crate::screens::Screen;
+use crate::ui::styles::ElysiumDescentColorPalette;
+
+/// Folder screenshots are saved to, relative to the working directory
+pub const SCREENSHOT_FOLDER: &str = "screenshots";
+
+/// Sent to enter or leave photo mode
+#[derive(Event, Debug, Clone, Copy)]
+pub struct TogglePhotoMode;
+
+/// Freezes the game and detaches a free camera for taking screenshots
+#[derive(Resource)]
+pub struct PhotoMode {
+    pub active: bool,
+    /// Exposure in EV100
+    pub exposure: f32,
+    /// Vertical field of view in radians
+    pub fov: f32,
+    pub depth_of_field: bool,
+    pub focal_distance: f32,
+    pub aperture_f_stops: f32,
+    restore: Option<PhotoRestore>,
+}
+
+impl Default for PhotoMode {
+    fn default() -> Self {
+        Self {
+            active: false,
+            exposure: Exposure::default().ev100,
+            fov: PerspectiveProjection::default().fov,
+            depth_of_field: false,
+            focal_distance: 10.0,
+            aperture_f_stops: 2.8,
+            restore: None,
+        }
+    }
+}
+
+/// What photo mode changed, to put back when it's closed
+struct PhotoRestore {
+    camera_mode: CameraMode,
+    fov: f32,
+    exposure: f32,
+}
+
+/// Root of the photo mode controls overlay
+#[derive(Component)]
+struct PhotoModeOverlay;
+
+/// Text showing the current photo settings
+#[derive(Component)]
+struct PhotoModeValues;
+
+/// Enters and leaves photo mode, pausing the game and swapping in the free camera.
+#[allow(clippy::too_many_arguments)]
+fn toggle_photo_mode(
+    mut commands: Commands,
+    mut toggle_reader: EventReader<TogglePhotoMode>,
+    mut photo_mode: ResMut<PhotoMode>,
+    mut camera_mode: ResMut<CameraMode>,
+    mut paused_by: ResMut<PausedBy>,
+    asset_server: Res<AssetServer>,
+    cameras: Query<(Entity, Option<&Projection>, Option<&Exposure>), With<FlyCam>>,
+    mut huds: Query<&mut Visibility, With<Hud>>,
+    overlays: Query<Entity, With<PhotoModeOverlay>>,
+) {
+    // Toggling twice in a frame cancels out
+    if toggle_reader.read().count() % 2 == 0 {
+        return;
+    }
+    let camera = cameras.single();
+
+    if let Some(restore) = photo_mode.restore.take() {
+        photo_mode.active = false;
+        *camera_mode = restore.camera_mode;
+        paused_by.resume(PauseReason::PhotoMode);
+
+        // The camera may already be gone with the rest of the gameplay scene
+        if let Ok((camera, projection, _)) = camera {
+            let mut camera = commands.entity(camera);
+            camera.remove::<DepthOfField>();
+            camera.insert(Exposure { ev100: restore.exposure });
+            if let Some(Projection::Perspective(perspective)) = projection {
+                camera.insert(Projection::Perspective(PerspectiveProjection {
+                    fov: restore.fov,
+                    ..perspective.clone()
+                }));
+            }
+        }
+
+        for mut visibility in &mut huds {
+            *visibility = Visibility::Inherited;
+        }
+        for overlay in &overlays {
+            commands.entity(overlay).despawn();
+        }
+        return;
+    }
+
+    let Ok((_, projection, exposure)) = camera else {
+        return;
+    };
+    let fov = match projection {
+        Some(Projection::Perspective(perspective)) => perspective.fov,
+        _ => PerspectiveProjection::default().fov,
+    };
+    let exposure = exposure.cloned().unwrap_or_default().ev100;
+    photo_mode.restore = Some(PhotoRestore {
+        camera_mode: *camera_mode,
+        fov,
+        exposure,
+    });
+    photo_mode.active = true;
+    photo_mode.fov = fov;
+    photo_mode.exposure = exposure;
+
+    *camera_mode = CameraMode::FreeDebug;
+    paused_by.pause(PauseReason::PhotoMode);
+
+    for mut visibility in &mut huds {
+        *visibility = Visibility::Hidden;
+    }
+
+    // Create UI
+    commands.spawn((
+        UiLayoutRoot::new_2d(),
+        UiFetchFromCamera::<0>,
+        PhotoModeOverlay,
+    )).with_children(|ui| {
+
+        // Spawn the title
+        ui.spawn((
+            UiLayout::window().pos(Rl((3.0, 4.0))).anchor(Anchor::TopLeft).pack(),
+            UiColor::from(Color::ELYSIUM_DESCENT_RED),
+            UiTextSize::from(Rh(4.0)),
+            Text2d::new("PHOTO MODE"),
+            TextFont {
+                font: asset_server.load("fonts/rajdhani/Rajdhani-SemiBold.ttf"),
+                font_size: 64.0,
+                ..default()
+            },
+            Pickable::IGNORE,
+        ));
+
+        // Spawn the current values
+        ui.spawn((
+            UiLayout::window().pos(Rl((3.0, 9.0))).anchor(Anchor::TopLeft).pack(),
+            UiColor::from(Color::ELYSIUM_DESCENT_BLUE),
+            UiTextSize::from(Rh(2.5)),
+            Text2d::new(photo_mode.describe()),
+            TextFont {
+                font: asset_server.load("fonts/rajdhani/Rajdhani-Medium.ttf"),
+                font_size: 64.0,
+                ..default()
+            },
+            PhotoModeValues,
+            Pickable::IGNORE,
+        ));
+
+        // Spawn the control hints
+        ui.spawn((
+            UiLayout::window().pos(Rl((3.0, 96.0))).anchor(Anchor::BottomLeft).pack(),
+            UiColor::from(Color::ELYSIUM_DESCENT_YELLOW),
+            UiTextSize::from(Rh(2.0)),
+            Text2d::new(
+                "WASD/QE FLY   RMB LOOK   WHEEL FOV   UP/DOWN EXPOSURE   T DEPTH OF FIELD   \
+                 LEFT/RIGHT FOCUS   PGUP/PGDN APERTURE   F12 CAPTURE   P EXIT",
+            ),
+            TextFont {
+                font: asset_server.load("fonts/rajdhani/Rajdhani-Medium.ttf"),
+                font_size: 64.0,
+                ..default()
+            },
+            Pickable::IGNORE,
+        ));
+    });
+}
+
+impl PhotoMode {
+    fn describe(&self) -> String {
+        let depth_of_field = match self.depth_of_field {
+            true => format!("f/{:.1} AT {:.1}M", self.aperture_f_stops, self.focal_distance),
+            false => "OFF".to_string(),
+        };
+        format!(
+            "FOV {:.0}°   EXPOSURE {:.1}   DEPTH OF FIELD {}",
+            self.fov.to_degrees(),
+            self.exposure,
+            depth_of_field,
+        )
+    }
+}
+
+/// Changes the photo settings from raw input, in real time since the game is paused.
+fn adjust_photo_settings(
+    time: Res<Time<Real>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut photo_mode: ResMut<PhotoMode>,
+) {
+    let delta = time.delta_secs();
+    let axis = |positive: KeyCode, negative: KeyCode| {
+        keyboard_input.pressed(positive) as i8 as f32 - keyboard_input.pressed(negative) as i8 as f32
+    };
+
+    let zoom: f32 = mouse_wheel_events.read().map(|e| e.y).sum();
+    let exposure = axis(KeyCode::ArrowDown, KeyCode::ArrowUp);
+    let focus = axis(KeyCode::ArrowRight, KeyCode::ArrowLeft);
+    let aperture = axis(KeyCode::PageUp, KeyCode::PageDown);
+    if zoom == 0.0
+        && exposure == 0.0
+        && focus == 0.0
+        && aperture == 0.0
+        && !keyboard_input.just_pressed(KeyCode::KeyT)
+    {
+        return;
+    }
+
+    photo_mode.fov = (photo_mode.fov - zoom * 2.0_f32.to_radians())
+        .clamp(10.0_f32.to_radians(), 120.0_f32.to_radians());
+    // A higher EV100 darkens the image, so up brightens
+    photo_mode.exposure = (photo_mode.exposure + exposure * 2.0 * delta).clamp(0.0, 20.0);
+    photo_mode.focal_distance =
+        (photo_mode.focal_distance * (1.0 + focus * delta)).clamp(0.5, 500.0);
+    photo_mode.aperture_f_stops =
+        (photo_mode.aperture_f_stops * (1.0 + aperture * delta)).clamp(0.5, 32.0);
+    if keyboard_input.just_pressed(KeyCode::KeyT) {
+        photo_mode.depth_of_field = !photo_mode.depth_of_field;
+    }
+}
+
+/// Applies the photo settings to the camera and shows them on the overlay.
+fn apply_photo_settings(
+    mut commands: Commands,
+    photo_mode: Res<PhotoMode>,
+    mut cameras: Query<(Entity, &mut Projection), With<FlyCam>>,
+    mut values: Query<&mut Text2d, With<PhotoModeValues>>,
+) {
+    for (entity, mut projection) in &mut cameras {
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            perspective.fov = photo_mode.fov;
+        }
+
+        let mut camera = commands.entity(entity);
+        camera.insert(Exposure { ev100: photo_mode.exposure });
+        if photo_mode.depth_of_field {
+            camera.insert(DepthOfField {
+                mode: DepthOfFieldMode::Bokeh,
+                focal_distance: photo_mode.focal_distance,
+                aperture_f_stops: photo_mode.aperture_f_stops,
+                ..default()
+            });
+        } else {
+            camera.remove::<DepthOfField>();
+        }
+    }
+
+    for mut text in &mut values {
+        text.0 = photo_mode.describe();
+    }
+}
+
+/// Saves a PNG of the screen without the photo mode overlay.
+fn take_screenshot(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut overlays: Query<&mut Visibility, With<PhotoModeOverlay>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F12) {
+        return;
+    }
+
+    if let Err(error) = std::fs::create_dir_all(SCREENSHOT_FOLDER) {
+        warn!("Couldn't create the {SCREENSHOT_FOLDER} folder: {error}");
+    }
+    let path = format!("{SCREENSHOT_FOLDER}/screenshot_{}.png", timestamp());
+    info!("Saving screenshot to {path}");
+
+    // The overlay is hidden for the captured frame and shown again afterwards
+    for mut visibility in &mut overlays {
+        *visibility = Visibility::Hidden;
+    }
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(path))
+        .observe(
+            |_: Trigger<ScreenshotCaptured>,
+             mut overlays: Query<&mut Visibility, With<PhotoModeOverlay>>| {
+                for mut visibility in &mut overlays {
+                    *visibility = Visibility::Inherited;
+                }
+            },
+        );
+}
+
+/// The current UTC date and time as `YYYY-MM-DD_HH-MM-SS_mmm`
+fn timestamp() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs = now.as_secs();
+    let (days, time) = ((secs / 86_400) as i64, secs % 86_400);
+
+    // Civil date from days since the epoch, after Howard Hinnant's `civil_from_days`
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}_{:03}",
+        time / 3_600,
+        time / 60 % 60,
+        time % 60,
+        now.subsec_millis(),
+    )
+}
+
+/// Closes photo mode when gameplay ends, so time doesn't stay paused.
+fn leave_photo_mode(photo_mode: Res<PhotoMode>, mut toggle_writer: EventWriter<TogglePhotoMode>) {
+    if photo_mode.active {
+        toggle_writer.write(TogglePhotoMode);
+    }
+}
+
+fn photo_mode_active(photo_mode: Res<PhotoMode>) -> bool {
+    photo_mode.active
+}
+
+pub struct PhotoModePlugin;
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhotoMode>()
+            .add_event::<TogglePhotoMode>()
+            .add_systems(OnExit(Screen::GamePlay), leave_photo_mode)
+            .add_systems(
+                Update,
+                (
+                    toggle_photo_mode,
+                    (
+                        adjust_photo_settings,
+                        apply_photo_settings.run_if(resource_changed::<PhotoMode>),
+                        take_screenshot,
+                    )
+                        .chain()
+                        .run_if(photo_mode_active),
+                )
+                    .chain(),
+            );
+    }
+}
@@ -1,6 +1,13 @@
-use crate::Player;
+use super::camera_mode::{
+    CameraMode, FreeCamera, TopDownCamera, apply_camera_rig, start_camera_transition,
+    sync_camera_mode, update_free_camera, update_top_down_camera,
+};
+use crate::systems::character_controller::{Stance, StanceSettings};
+use crate::systems::targeting::LockOnTarget;
+use crate::{ControlScheme, GameSettings, Player};
+use avian3d::prelude::*;
 use bevy::{
-    input::mouse::MouseWheel,
+    input::mouse::{MouseMotion, MouseWheel},
     prelude::*,
 };
 
@@ -13,6 +20,16 @@ pub struct FlyCam {
     pub smooth_speed: f32,
     pub min_vertical_angle: f32,
     pub max_vertical_angle: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    pub zoom_speed: f32,
+    pub mouse_sensitivity: f32,
+    pub stick_sensitivity: f32,
+    /// Orbit angle around the player. With tank controls it is relative to the
+    /// player's facing, otherwise it is the camera's own yaw.
+    pub yaw: f32,
+    /// Orbit angle above the player, clamped to the vertical angle limits
+    pub pitch: f32,
 }
 
 impl Default for FlyCam {
@@ -24,45 +41,218 @@ impl Default for FlyCam {
             smooth_speed: 5.0,      // Camera smoothing speed
             min_vertical_angle: -30.0_f32.to_radians(), // Minimum look down angle
             max_vertical_angle: 60.0_f32.to_radians(),  // Maximum look up angle
+            min_distance: 12.0,
+            max_distance: 40.0,     // Significantly increased zoom range
+            zoom_speed: 0.5,
+            mouse_sensitivity: 0.005,
+            stick_sensitivity: 2.5,
+            yaw: 0.0,
+            pitch: 15.0_f32.to_radians(),
         }
     }
 }
 
-fn update_camera_position(
+impl FlyCam {
+    /// The direction from the orbit pivot towards the camera
+    pub fn orbit_direction(&self, base_yaw: f32) -> Vec3 {
+        let yaw = base_yaw + self.yaw;
+        Vec3::new(
+            yaw.sin() * self.pitch.cos(),
+            self.pitch.sin(),
+            yaw.cos() * self.pitch.cos(),
+        )
+    }
+}
+
+/// The pose the active [`CameraMode`] wants the camera in. The transform
+/// follows it directly, or eases towards it after a mode switch.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct CameraRig {
+    pub translation: Vec3,
+    pub rotation: Quat,
+}
+
+impl From<Transform> for CameraRig {
+    fn from(transform: Transform) -> Self {
+        Self {
+            translation: transform.translation,
+            rotation: transform.rotation,
+        }
+    }
+}
+
+/// Keeps a follow camera from clipping into level geometry
+#[derive(Component)]
+pub struct CameraCollision {
+    /// Radius of the sphere cast from the player towards the camera
+    pub radius: f32,
+    /// Speed at which the camera returns to its full distance once unblocked
+    pub ease_out_speed: f32,
+    /// Make geometry between the camera and the player see-through
+    pub fade_occluders: bool,
+    /// Alpha used for faded occluders
+    pub fade_alpha: f32,
+    /// Current unobstructed distance from the player
+    pub distance: f32,
+}
+
+impl Default for CameraCollision {
+    fn default() -> Self {
+        Self {
+            radius: 0.5,
+            ease_out_speed: 3.0,
+            fade_occluders: false,
+            fade_alpha: 0.3,
+            distance: f32::MAX,
+        }
+    }
+}
+
+impl CameraCollision {
+    /// Limits the camera offset from `pivot` to the space that's free of geometry.
+    /// The camera pulls in at once when blocked and eases back out when clear.
+    pub fn constrain(
+        &mut self,
+        spatial_query: &SpatialQuery,
+        pivot: Vec3,
+        offset: Vec3,
+        filter: &SpatialQueryFilter,
+        delta: f32,
+    ) -> Vec3 {
+        let Ok((direction, full_distance)) = Dir3::new_and_length(offset) else {
+            return offset;
+        };
+
+        let allowed = spatial_query
+            .cast_shape(
+                &Collider::sphere(self.radius),
+                pivot,
+                Quat::IDENTITY,
+                direction,
+                &ShapeCastConfig::from_max_distance(full_distance),
+                filter,
+            )
+            .map_or(full_distance, |hit| hit.distance);
+
+        self.distance = if allowed < self.distance {
+            allowed
+        } else {
+            self.distance.lerp(allowed, (self.ease_out_speed * delta).min(1.0))
+        };
+
+        direction * self.distance.min(full_distance)
+    }
+
+    /// Whether the camera is currently pulled in from `full_distance`
+    pub fn is_blocked(&self, full_distance: f32) -> bool {
+        self.distance < full_distance - f32::EPSILON
+    }
+}
+
+/// Remembers the material an occluder had before it was faded
+#[derive(Component)]
+pub struct FadedOccluder {
+    original: Handle<StandardMaterial>,
+}
+
+/// Fades the meshes between follow cameras and the player, restoring them once clear.
+fn fade_occluders(
+    mut commands: Commands,
+    spatial_query: SpatialQuery,
+    cameras: Query<(&GlobalTransform, &CameraCollision)>,
+    players: Query<(Entity, &GlobalTransform), With<Player>>,
+    mut meshes: Query<&mut MeshMaterial3d<StandardMaterial>>,
+    faded: Query<(Entity, &FadedOccluder)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut occluders = Vec::new();
+    if let Ok((player, player_transform)) = players.single() {
+        for (camera_transform, collision) in &cameras {
+            if !collision.fade_occluders {
+                continue;
+            }
+            let origin = camera_transform.translation();
+            let Ok((direction, distance)) =
+                Dir3::new_and_length(player_transform.translation() - origin)
+            else {
+                continue;
+            };
+            occluders.extend(
+                spatial_query
+                    .ray_hits(
+                        origin,
+                        direction,
+                        distance,
+                        8,
+                        true,
+                        &SpatialQueryFilter::from_excluded_entities([player]),
+                    )
+                    .into_iter()
+                    .map(|hit| hit.entity),
+            );
+        }
+    }
+
+    // Restore occluders that are no longer in the way
+    for (entity, faded) in &faded {
+        if occluders.contains(&entity) {
+            continue;
+        }
+        if let Ok(mut material) = meshes.get_mut(entity) {
+            material.0 = faded.original.clone();
+        }
+        commands.entity(entity).remove::<FadedOccluder>();
+    }
+
+    // Swap new occluders to a translucent copy of their material
+    for entity in occluders {
+        if faded.contains(entity) {
+            continue;
+        }
+        let Ok(mut material) = meshes.get_mut(entity) else {
+            continue;
+        };
+        let Some(mut translucent) = materials.get(&material.0).cloned() else {
+            continue;
+        };
+        let alpha = cameras
+            .iter()
+            .map(|(_, collision)| collision.fade_alpha)
+            .fold(1.0, f32::min);
+        translucent.base_color.set_alpha(alpha);
+        translucent.alpha_mode = AlphaMode::Blend;
+
+        let original = std::mem::replace(&mut material.0, materials.add(translucent));
+        commands.entity(entity).insert(FadedOccluder { original });
+    }
+}
+
+/// Orbits follow cameras with the mouse while the right button is held, or the right stick.
+fn handle_camera_orbit(
     time: Res<Time>,
-    player_query: Query<&Transform, (With<Player>, Without<FlyCam>)>,
-    mut camera_query: Query<(&FlyCam, &mut Transform), Without<Player>>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    mut query: Query<&mut FlyCam>,
 ) {
-    // Get player transform first
-    let Ok(player_transform) = player_query.single() else {
-        return;
+    let mouse_delta: Vec2 = mouse_motion_events.read().map(|e| e.delta).sum();
+    let mouse_delta = if mouse_input.pressed(MouseButton::Right) {
+        mouse_delta
+    } else {
+        Vec2::ZERO
     };
 
-    // Then update camera positions
-    for (camera, mut transform) in camera_query.iter_mut() {
-        // Calculate desired camera position
-        let player_pos = player_transform.translation;
-        let player_forward = player_transform.forward();
-        
-        // Calculate camera position in front of player (opposite of before)
-        let target_pos = player_pos + player_forward * camera.distance;
-        let target_pos = Vec3::new(
-            target_pos.x,
-            player_pos.y + camera.height_offset,
-            target_pos.z
-        );
-        
-        // Smoothly move camera to target position
-        transform.translation = transform.translation.lerp(
-            target_pos,
-            (camera.smooth_speed * time.delta_secs()).min(1.0)
-        );
-        
-        // Calculate look target (behind player now)
-        let look_target = player_pos - player_forward * camera.look_ahead;
-        
-        // Make camera look at target
-        transform.look_at(look_target, Vec3::Y);
+    let stick_delta: Vec2 = gamepads
+        .iter()
+        .map(|gamepad| gamepad.right_stick())
+        .sum::<Vec2>()
+        * time.delta_secs();
+
+    for mut camera in &mut query {
+        let delta = mouse_delta * camera.mouse_sensitivity
+            + Vec2::new(stick_delta.x, -stick_delta.y) * camera.stick_sensitivity;
+        camera.yaw -= delta.x;
+        camera.pitch = (camera.pitch + delta.y).clamp(camera.min_vertical_angle, camera.max_vertical_angle);
     }
 }
 
@@ -73,15 +263,134 @@ fn handle_camera_zoom(
     let delta: f32 = mouse_wheel_events.read().map(|e| e.y).sum();
     for mut camera in &mut query {
         // Adjust camera distance with zoom
-        camera.distance -= delta * 0.5;
-        camera.distance = camera.distance.clamp(12.0, 40.0); // Significantly increased zoom range
+        camera.distance = (camera.distance - delta * camera.zoom_speed)
+            .clamp(camera.min_distance, camera.max_distance);
     }
 }
 
-/// Plugin for third-person camera behavior
-pub struct PlayerPlugin;
-impl Plugin for PlayerPlugin {
+/// Moves follow cameras to their orbit around the player and looks ahead of them.
+/// While locked on, they frame the player and the target together instead.
+fn update_camera_position(
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    player_query: Query<
+        (
+            Entity,
+            &Transform,
+            &Stance,
+            &StanceSettings,
+            &ControlScheme,
+            Option<&LockOnTarget>,
+        ),
+        (With<Player>, Without<FlyCam>),
+    >,
+    targets: Query<&GlobalTransform>,
+    mut camera_query: Query<(&FlyCam, Option<&mut CameraCollision>, &mut CameraRig)>,
+) {
+    // Get player transform first
+    let Ok((player, player_transform, stance, stance_settings, control_scheme, lock_on)) =
+        player_query.single()
+    else {
+        return;
+    };
+    let lock_on_pos = lock_on
+        .and_then(|target| targets.get(target.0).ok())
+        .map(|target| target.translation());
+
+    // The model faces the opposite way of the transform's forward
+    let player_facing = -player_transform.forward().as_vec3();
+    let base_yaw = match (lock_on_pos, control_scheme) {
+        // Sit behind the player, on the far side from the target
+        (Some(lock_on_pos), _) => {
+            let away = player_transform.translation - lock_on_pos;
+            away.x.atan2(away.z)
+        }
+        // The default orbit sits in front of the player's back, like the tank controls expect
+        (None, ControlScheme::Tank) => (-player_facing.x).atan2(-player_facing.z),
+        (None, ControlScheme::CameraRelative) => 0.0,
+    };
+
+    let mut player_pos = player_transform.translation;
+    if *stance == Stance::Crouch {
+        player_pos.y -= stance_settings.crouch_camera_drop;
+    }
+    let filter = SpatialQueryFilter::from_excluded_entities([player]);
+    let delta = time.delta_secs();
+
+    // Then update camera positions
+    for (camera, collision, mut rig) in camera_query.iter_mut() {
+        let (pivot, distance) = match lock_on_pos {
+            // Pull back far enough to keep both in view around their midpoint
+            Some(lock_on_pos) => {
+                let midpoint = player_pos.midpoint(lock_on_pos.with_y(player_pos.y));
+                let half_separation = player_pos.distance(midpoint);
+                (
+                    midpoint + Vec3::Y * camera.height_offset,
+                    camera.distance.max(half_separation + camera.min_distance),
+                )
+            }
+            None => (player_pos + Vec3::Y * camera.height_offset, camera.distance),
+        };
+        let offset = camera.orbit_direction(base_yaw) * distance;
+
+        let target_pos = match collision {
+            Some(mut collision) => {
+                let target_pos =
+                    pivot + collision.constrain(&spatial_query, pivot, offset, &filter, delta);
+                // Smoothing would drag the camera through the wall, so snap instead
+                if collision.is_blocked(distance) {
+                    rig.translation = target_pos;
+                }
+                target_pos
+            }
+            None => pivot + offset,
+        };
+
+        // Smoothly move camera to target position
+        rig.translation = rig
+            .translation
+            .lerp(target_pos, (camera.smooth_speed * delta).min(1.0));
+
+        // Look slightly ahead of the player, or straight at the midpoint when locked on
+        let look_target = match lock_on_pos {
+            Some(_) => pivot,
+            None => pivot + player_facing.with_y(0.0) * camera.look_ahead,
+        };
+        rig.rotation = Transform::from_translation(rig.translation)
+            .looking_at(look_target, Vec3::Y)
+            .rotation;
+    }
+}
+
+fn game_running(time: Res<Time<Virtual>>) -> bool {
+    !time.is_paused()
+}
+
+/// Plugin for the gameplay camera and its [`CameraMode`]s
+pub struct FollowCameraPlugin;
+impl Plugin for FollowCameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (update_camera_position, handle_camera_zoom));
+        app.init_resource::<CameraMode>()
+            .init_resource::<TopDownCamera>()
+            .init_resource::<FreeCamera>()
+            .add_systems(
+                Update,
+                (
+                    sync_camera_mode.run_if(resource_changed::<GameSettings>),
+                    start_camera_transition.run_if(resource_changed::<CameraMode>),
+                    (
+                        // The world is frozen, so the view is too
+                        (handle_camera_orbit, handle_camera_zoom).run_if(game_running),
+                        update_camera_position,
+                    )
+                        .chain()
+                        .run_if(resource_equals(CameraMode::ThirdPerson)),
+                    update_top_down_camera.run_if(resource_equals(CameraMode::TopDown)),
+                    update_free_camera.run_if(resource_equals(CameraMode::FreeDebug)),
+                    apply_camera_rig,
+                    fade_occluders,
+                )
+                    .chain(),
+            );
     }
 }
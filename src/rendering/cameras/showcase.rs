@@ -3,32 +3,152 @@ use bevy::{
     prelude::*,
 };
 
+/// A point to orbit around and how far away to stay
+#[derive(Reflect, Clone, Copy, PartialEq, Debug)]
+pub struct FocusPreset {
+    pub orbit: Vec3,
+    pub distance: f32,
+}
+
+/// Parts of the character preview the camera can focus on
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ShowcaseFocus {
+    Face,
+    FullBody,
+}
+
+impl ShowcaseFocus {
+    pub const ALL: [ShowcaseFocus; 2] = [ShowcaseFocus::Face, ShowcaseFocus::FullBody];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ShowcaseFocus::Face => "Face",
+            ShowcaseFocus::FullBody => "Full body",
+        }
+    }
+}
+
 #[derive(Component, Reflect, Clone, PartialEq, Debug)]
 pub struct ShowcaseCamera {
     pub orbit: Vec3,
     pub distance: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
     pub mouse_sensitivity: f32,
+    pub stick_sensitivity: f32,
     pub zoom_scale: f32,
+    /// Zoom per second with a gamepad trigger fully pressed, relative to the distance
+    pub trigger_zoom_speed: f32,
+    /// How quickly the orbit slows down after it's let go. Higher stops sooner.
+    pub inertia_decay: f32,
+    /// How quickly the orbit and distance ease to their targets
+    pub smooth_speed: f32,
+    /// Degrees per second the camera turns on its own while idle
+    pub auto_rotate_speed: f32,
+    /// Seconds without input before auto-rotate kicks in
+    pub idle_delay: f32,
+    pub face: FocusPreset,
+    pub full_body: FocusPreset,
+    target_orbit: Vec3,
+    target_distance: f32,
+    /// Orbit speed in degrees per second
+    velocity: Vec2,
+    idle: f32,
 }
 
 impl ShowcaseCamera {
+    /// Eases the camera to a focus preset
+    pub fn focus(&mut self, focus: ShowcaseFocus) {
+        let preset = match focus {
+            ShowcaseFocus::Face => self.face,
+            ShowcaseFocus::FullBody => self.full_body,
+        };
+        self.target_orbit = preset.orbit;
+        self.target_distance = preset.distance.clamp(self.min_distance, self.max_distance);
+    }
+
     pub fn rotate(
+        time: Res<Time>,
         mut mouse_motion_events: EventReader<MouseMotion>,
         mouse_input: Res<ButtonInput<MouseButton>>,
-        mut query: Query<(&ShowcaseCamera, &mut Transform)>,
+        gamepads: Query<&Gamepad>,
+        mut query: Query<&mut ShowcaseCamera>,
     ) {
-        let mut delta = Vec2::ZERO;
-        if mouse_input.pressed(MouseButton::Left) {
-            delta = mouse_motion_events.read().map(|e| e.delta).sum();
-        }
+        let delta = time.delta_secs();
+        let mut mouse_delta: Vec2 = mouse_motion_events.read().map(|e| e.delta).sum();
         if mouse_input.just_pressed(MouseButton::Left) {
-            delta = Vec2::ZERO;
+            mouse_delta = Vec2::ZERO;
+        }
+        let dragging = mouse_input.pressed(MouseButton::Left);
+        let stick: Vec2 = gamepads.iter().map(|gamepad| gamepad.right_stick()).sum();
+
+        for mut camera in &mut query {
+            if dragging && delta > 0.0 {
+                // Follow the mouse exactly, keeping its speed for when it's let go
+                camera.velocity = mouse_delta * camera.mouse_sensitivity / delta;
+            } else if stick != Vec2::ZERO {
+                camera.velocity = Vec2::new(stick.x, -stick.y) * camera.stick_sensitivity;
+            } else {
+                let decay = (-camera.inertia_decay * delta).exp();
+                camera.velocity *= decay;
+            }
+
+            if dragging || stick != Vec2::ZERO {
+                camera.idle = 0.0;
+            } else {
+                camera.idle += delta;
+            }
+
+            // Ease into a slow turn once left alone
+            if camera.idle >= camera.idle_delay {
+                let auto_rotate = Vec2::new(-camera.auto_rotate_speed, 0.0);
+                let blend = (camera.smooth_speed * delta).min(1.0);
+                camera.velocity = camera.velocity.lerp(auto_rotate, blend);
+            }
+        }
+    }
+
+    pub fn zoom(
+        time: Res<Time>,
+        mut mouse_wheel_events: EventReader<MouseWheel>,
+        gamepads: Query<&Gamepad>,
+        mut query: Query<&mut ShowcaseCamera>,
+    ) {
+        let delta: f32 = mouse_wheel_events.read().map(|e| e.y).sum();
+        // Right trigger zooms in, left trigger zooms out
+        let trigger: f32 = gamepads
+            .iter()
+            .map(|gamepad| {
+                gamepad.get(GamepadButton::RightTrigger2).unwrap_or(0.0)
+                    - gamepad.get(GamepadButton::LeftTrigger2).unwrap_or(0.0)
+            })
+            .sum();
+
+        for mut camera in &mut query {
+            let mut distance = camera.target_distance;
+            if camera.zoom_scale != 0.0 {
+                distance -= delta * distance / camera.zoom_scale;
+            }
+            distance -= trigger * distance * camera.trigger_zoom_speed * time.delta_secs();
+            camera.target_distance = distance.clamp(camera.min_distance, camera.max_distance);
+            if delta != 0.0 || trigger != 0.0 {
+                camera.idle = 0.0;
+            }
         }
-        for (camera, mut transform) in &mut query {
+    }
+
+    /// Turns the camera by its orbit speed and eases it to its target orbit and distance
+    pub fn apply(time: Res<Time>, mut query: Query<(&mut ShowcaseCamera, &mut Transform)>) {
+        let delta = time.delta_secs();
+        for (mut camera, mut transform) in &mut query {
+            let blend = (camera.smooth_speed * delta).min(1.0);
+            camera.orbit = camera.orbit.lerp(camera.target_orbit, blend);
+            camera.distance = camera.distance.lerp(camera.target_distance, blend);
+
             // ROTATION
             let (mut rx, mut ry, rz) = transform.rotation.to_euler(EulerRot::YXZ);
-            rx += (-delta.x * camera.mouse_sensitivity).to_radians();
-            ry += (-delta.y * camera.mouse_sensitivity).to_radians();
+            rx += (-camera.velocity.x * delta).to_radians();
+            ry += (-camera.velocity.y * delta).to_radians();
             ry = ry.clamp(-90_f32.to_radians(), 90_f32.to_radians());
             transform.rotation = Quat::from_euler(EulerRot::YXZ, rx, ry, rz);
 
@@ -46,27 +166,37 @@ impl ShowcaseCamera {
             transform.translation.y += -tz;
         }
     }
-
-    pub fn zoom(
-        mut mouse_wheel_events: EventReader<MouseWheel>,
-        mut query: Query<&mut ShowcaseCamera>,
-    ) {
-        let delta: f32 = mouse_wheel_events.read().map(|e| e.y).sum();
-        for mut camera in &mut query {
-            if camera.zoom_scale != 0.0 {
-                camera.distance -= delta * camera.distance / camera.zoom_scale;
-            }
-        }
-    }
 }
 
 impl Default for ShowcaseCamera {
     fn default() -> Self {
-        Self {
+        let full_body = FocusPreset {
             orbit: Vec3::ZERO,
             distance: 2.0,
+        };
+        Self {
+            orbit: full_body.orbit,
+            distance: full_body.distance,
+            min_distance: 0.5,
+            max_distance: 5.0,
             mouse_sensitivity: 0.1,
+            stick_sensitivity: 120.0,
             zoom_scale: 25.0,
+            trigger_zoom_speed: 1.0,
+            inertia_decay: 4.0,
+            smooth_speed: 6.0,
+            auto_rotate_speed: 10.0,
+            idle_delay: 5.0,
+            face: FocusPreset {
+                // The head of the preview model
+                orbit: Vec3::new(-0.3, 1.0, -1.0),
+                distance: 0.8,
+            },
+            full_body,
+            target_orbit: full_body.orbit,
+            target_distance: full_body.distance,
+            velocity: Vec2::ZERO,
+            idle: 0.0,
         }
     }
 }
@@ -75,6 +205,9 @@ pub struct ShowcaseCameraPlugin;
 
 impl Plugin for ShowcaseCameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (ShowcaseCamera::rotate, ShowcaseCamera::zoom));
+        app.add_systems(
+            Update,
+            (ShowcaseCamera::rotate, ShowcaseCamera::zoom, ShowcaseCamera::apply).chain(),
+        );
     }
 }
@@ -1,8 +1,9 @@
-use bevy::{prelude::*, render::view::RenderLayers, sprite::Anchor};
+use bevy::{prelude::*, sprite::Anchor};
 use bevy_lunex::*;
 
 use super::Screen;
-use crate::game::resources::MainTrack;
+use super::transition::RequestScreen;
+use crate::game::resources::{MainTrack, RunSummary};
 use crate::ui::styles::ElysiumDescentColorPalette;
 
 // ===== PLUGIN SETUP =====
@@ -10,7 +11,8 @@ use crate::ui::styles::ElysiumDescentColorPalette;
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::GameOver), GameOverScene::spawn)
         .add_systems(OnExit(Screen::GameOver), despawn_scene::<GameOverScene>)
-        .init_resource::<MainTrack>();
+        .init_resource::<MainTrack>()
+        .init_resource::<RunSummary>();
 }
 
 // ===== SYSTEMS =====
@@ -29,11 +31,156 @@ struct GameOverScene;
 // ===== GAME OVER IMPLEMENTATION =====
 
 impl GameOverScene {
-    fn spawn(
-        mut commands: Commands,
-        asset_server: Res<AssetServer>,
-        mut images: ResMut<Assets<Image>>,
-        mut materials: ResMut<Assets<StandardMaterial>>,
-    ) {
+    fn spawn(mut commands: Commands, asset_server: Res<AssetServer>, summary: Res<RunSummary>) {
+        let minutes = (summary.time_survived / 60.0) as u32;
+        let seconds = (summary.time_survived % 60.0) as u32;
+        let lines = [
+            ("Time survived", format!("{minutes}:{seconds:02}")),
+            ("Damage taken", summary.damage_taken.to_string()),
+            ("Hard landings", summary.hard_landings.to_string()),
+        ];
+
+        // Create UI
+        commands.spawn((
+            UiLayoutRoot::new_2d(),
+            // Make the UI synchronized with camera viewport size
+            UiFetchFromCamera::<0>,
+            // A scene marker for later mass scene despawn, not UI related
+            GameOverScene
+        )).with_children(|ui| {
+
+            // Spawn the background
+            ui.spawn((
+                Name::new("Background"),
+                UiLayout::solid().size((1920.0, 1080.0)).scaling(Scaling::Fill).pack(),
+                Sprite::from_image(asset_server.load("images/ui/background.png")),
+            ));
+
+            // Spawn the title
+            ui.spawn((
+                UiLayout::window().pos(Rl((50.0, 20.0))).anchor(Anchor::Center).pack(),
+                UiColor::from(Color::ELYSIUM_DESCENT_RED),
+                UiTextSize::from(Rh(10.0)),
+                Text2d::new("YOU DIED"),
+                TextFont {
+                    font: asset_server.load("fonts/rajdhani/Rajdhani-SemiBold.ttf"),
+                    font_size: 64.0,
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ));
+
+            // Spawn the run summary
+            ui.spawn((
+                Name::new("Run Summary"),
+                UiLayout::window().pos(Rl((50.0, 32.0))).anchor(Anchor::TopCenter).size(Rl((30.0, 24.0))).pack(),
+            )).with_children(|ui| {
+
+                let size = 100.0 / lines.len() as f32;
+                for (i, (label, value)) in lines.into_iter().enumerate() {
+
+                    // Spawn the label
+                    ui.spawn((
+                        UiLayout::window().pos(Rl((0.0, size * (i as f32 + 0.5)))).anchor(Anchor::CenterLeft).pack(),
+                        UiColor::from(Color::ELYSIUM_DESCENT_RED),
+                        UiTextSize::from(Rh(3.5)),
+                        Text2d::new(label.to_ascii_uppercase()),
+                        TextFont {
+                            font: asset_server.load("fonts/rajdhani/Rajdhani-Medium.ttf"),
+                            font_size: 64.0,
+                            ..default()
+                        },
+                        Pickable::IGNORE,
+                    ));
+
+                    // Spawn the value
+                    ui.spawn((
+                        UiLayout::window().pos(Rl((100.0, size * (i as f32 + 0.5)))).anchor(Anchor::CenterRight).pack(),
+                        UiColor::from(Color::ELYSIUM_DESCENT_BLUE),
+                        UiTextSize::from(Rh(3.5)),
+                        Text2d::new(value),
+                        TextFont {
+                            font: asset_server.load("fonts/rajdhani/Rajdhani-Bold.ttf"),
+                            font_size: 64.0,
+                            ..default()
+                        },
+                        Pickable::IGNORE,
+                    ));
+                }
+            });
+
+            // Spawn button boundary
+            ui.spawn((
+                Name::new("Button List"),
+                UiLayout::window().pos(Rl((50.0, 62.0))).anchor(Anchor::TopCenter).size(Rl((24.0, 20.0))).pack(),
+            )).with_children(|ui| {
+
+                // Spawn buttons
+                let gap = 6.0;
+                let size = 30.0;
+                let mut offset = 0.0;
+                for button in ["Retry", "Main menu"] {
+
+                    // Spawn the button
+                    let mut button_entity = ui.spawn((
+                        Name::new(button),
+                        UiLayout::window().y(Rl(offset)).size(Rl((100.0, size))).pack(),
+                        OnHoverSetCursor::new(bevy::window::SystemCursorIcon::Pointer),
+                    ));
+                    button_entity.with_children(|ui| {
+                        // Spawn the image
+                        ui.spawn((
+                            UiLayout::new(vec![
+                                (UiBase::id(), UiLayout::window().full()),
+                                (UiHover::id(), UiLayout::window().x(Rl(5.0)).full())
+                            ]),
+                            UiHover::new().forward_speed(20.0).backward_speed(4.0),
+                            UiColor::new(vec![
+                                (UiBase::id(), Color::ELYSIUM_DESCENT_RED.with_alpha(0.15)),
+                                (UiHover::id(), Color::ELYSIUM_DESCENT_YELLOW.with_alpha(1.2))
+                            ]),
+                            Sprite {
+                                image: asset_server.load("images/ui/components/button_symetric_sliced.png"),
+                                image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::all(32.0), ..default() }),
+                                ..default()
+                            },
+                            Pickable::IGNORE,
+                        )).with_children(|ui| {
+
+                            // Spawn the text
+                            ui.spawn((
+                                UiLayout::window().pos((Rh(40.0), Rl(50.0))).anchor(Anchor::CenterLeft).pack(),
+                                UiColor::new(vec![
+                                    (UiBase::id(), Color::ELYSIUM_DESCENT_RED),
+                                    (UiHover::id(), Color::ELYSIUM_DESCENT_YELLOW.with_alpha(1.2))
+                                ]),
+                                UiHover::new().forward_speed(20.0).backward_speed(4.0),
+                                UiTextSize::from(Rh(60.0)),
+                                Text2d::new(button),
+                                TextFont {
+                                    font: asset_server.load("fonts/rajdhani/Rajdhani-Medium.ttf"),
+                                    font_size: 64.0,
+                                    ..default()
+                                },
+                                Pickable::IGNORE,
+                            ));
+                        });
+
+                    // Enable the transition on hover
+                    }).observe(hover_set::<Pointer<Over>, true>).observe(hover_set::<Pointer<Out>, false>);
+
+                    // Assign a functionality to the buttons
+                    let target = match button {
+                        "Retry" => Screen::GeneratingLevel,
+                        _ => Screen::MainMenu,
+                    };
+                    button_entity.observe(move |_: Trigger<Pointer<Click>>, mut requests: EventWriter<RequestScreen>| {
+                        requests.write(RequestScreen(target.clone()));
+                    });
+
+                    offset += gap + size;
+                }
+            });
+        });
     }
 }
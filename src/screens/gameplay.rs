@@ -1,24 +1,53 @@
 use avian3d::prelude::*;
-use bevy::prelude::*;
-use std::time::Duration;
-
-use super::Screen;
-use crate::game::resources::MainTrack;
+use bevy::{gltf::Gltf, prelude::*, scene::SceneInstanceReady};
+
+use super::generating_level::LevelBuild;
+use super::transition::RequestScreen;
+use super::{InRun, Screen};
+use crate::game::resources::{GameSettings, MainTrack, PauseReason, PausedBy, RunSummary};
+use crate::rendering::cameras::camera_shake::CameraShake;
+use crate::rendering::cameras::cinematic::PlayCinematic;
+use crate::rendering::cameras::minimap::{MinimapPlugin, spawn_minimap};
+use crate::rendering::cameras::photo_mode::PhotoModePlugin;
 use crate::rendering::cameras::player_camera::*;
-use crate::Player;
+use crate::systems::hazards::FallDamage;
+use crate::{DamageSource, Health, Player, PlayerDamaged, Stamina};
+use crate::systems::animation::LocomotionAnimations;
 use crate::systems::character_controller::CharacterControllerBundle;
 
 // ===== PLUGIN SETUP =====
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Screen::GamePlay), PlayingScene::spawn_environment)
-        .add_systems(Update, PlayingScene::check_environment_loaded)
-        .add_systems(OnExit(Screen::GamePlay), despawn_scene::<PlayingScene>)
-        .add_plugins(PhysicsPlugins::default())
-        // .add_plugins(PhysicsDebugPlugin::default())
-        .insert_resource(ClearColor(Color::srgba_u8(135, 206, 250, 191)))
-        .init_resource::<MainTrack>()
-        .init_resource::<EnvironmentLoadTimer>();
+    app.add_systems(
+        OnEnter(Screen::GamePlay),
+        (
+            close_pause_menu,
+            // Coming back from the pause menu or inventory keeps the existing player
+            PlayingScene::spawn_player.run_if(not(any_with_component::<Player>)),
+        ),
+    )
+    .add_systems(OnEnter(Screen::Pause), open_pause_menu)
+    .add_systems(OnEnter(Screen::GeneratingLevel), PlayingScene::spawn_environment)
+    .add_systems(
+        Update,
+        (
+            PlayingScene::track_run,
+            PlayingScene::check_player_defeated,
+        )
+            .run_if(in_state(Screen::GamePlay)),
+    )
+    .add_systems(Update, apply_pause.run_if(resource_changed::<PausedBy>))
+    // The world and anything still pausing it go with the run
+    .add_systems(OnExit(InRun), (despawn_scene::<PlayingScene>, end_pauses))
+    .add_plugins(PhysicsPlugins::default())
+    .add_plugins(FollowCameraPlugin)
+    .add_plugins(MinimapPlugin)
+    .add_plugins(PhotoModePlugin)
+    // .add_plugins(PhysicsDebugPlugin::default())
+    .insert_resource(ClearColor(Color::srgba_u8(135, 206, 250, 191)))
+    .init_resource::<MainTrack>()
+    .init_resource::<RunSummary>()
+    .init_resource::<PausedBy>();
 }
 
 // ===== SYSTEMS =====
@@ -29,31 +58,52 @@ fn despawn_scene<S: Component>(mut commands: Commands, query: Query<Entity, With
     }
 }
 
-// ===== RESOURCES & COMPONENTS =====
-
-#[derive(Component)]
-struct PlayingScene;
+/// Freezes gameplay while the pause menu is open, keeping the world around
+fn open_pause_menu(mut paused_by: ResMut<PausedBy>) {
+    paused_by.pause(PauseReason::PauseMenu);
+}
 
-#[derive(Component)]
-struct EnvironmentMarker;
+/// Releases only the pause menu's hold, anything else pausing the game stays paused
+fn close_pause_menu(mut paused_by: ResMut<PausedBy>) {
+    paused_by.resume(PauseReason::PauseMenu);
+}
 
-#[derive(Resource)]
-struct EnvironmentLoadTimer {
-    timer: Timer,
+fn end_pauses(mut paused_by: ResMut<PausedBy>) {
+    paused_by.clear();
 }
 
-impl Default for EnvironmentLoadTimer {
-    fn default() -> Self {
-        Self {
-            timer: Timer::new(Duration::from_secs(1), TimerMode::Once),
-        }
+/// Freezes gameplay and physics time while anything holds them paused
+fn apply_pause(
+    paused_by: Res<PausedBy>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut physics_time: ResMut<Time<Physics>>,
+) {
+    if paused_by.is_paused() {
+        virtual_time.pause();
+        physics_time.pause();
+    } else {
+        virtual_time.unpause();
+        physics_time.unpause();
     }
 }
 
+// ===== RESOURCES & COMPONENTS =====
+
+#[derive(Component, Clone)]
+struct PlayingScene;
+
+#[derive(Component)]
+struct EnvironmentMarker;
+
 // ===== PLAYING SCENE IMPLEMENTATION =====
 
 impl PlayingScene {
-    fn spawn_environment(mut commands: Commands, assets: Res<AssetServer>) {
+    /// Builds the level while the loading screen tracks its progress
+    fn spawn_environment(
+        mut commands: Commands,
+        assets: Res<AssetServer>,
+        mut images: ResMut<Assets<Image>>,
+    ) {
         commands.insert_resource(AmbientLight {
             color: Color::srgb_u8(68, 71, 88),
             brightness: 120.0,
@@ -61,20 +111,39 @@ impl PlayingScene {
         });
 
         // Environment (see the `collider_constructors` example for creating colliders from scenes)
-        let scene_handle = assets.load("models/fantasy_environment_level_set.glb#Scene0");
-        commands.spawn((
-            Name::new("Environment"),
-            EnvironmentMarker,
-            SceneRoot(scene_handle),
-            Transform {
-                translation: Vec3::new(0.0, -1.5, 0.0),
-                rotation: Quat::from_rotation_y(-core::f32::consts::PI * 0.5),
-                scale: Vec3::splat(0.05), // ⬅️ scale environment down
-            },
-            ColliderConstructorHierarchy::new(ColliderConstructor::TrimeshFromMesh),
-            RigidBody::Static,
-            DebugRender::default(),
-        ));
+        let scene_handle: Handle<Scene> =
+            assets.load("models/fantasy_environment_level_set.glb#Scene0");
+
+        // Load the player up front so it is ready as soon as gameplay starts
+        commands.insert_resource(LevelBuild::new([
+            scene_handle.clone().untyped(),
+            assets.load::<Scene>("models/person.glb#Scene0").untyped(),
+            assets.load::<Gltf>("models/person.glb").untyped(),
+        ]));
+
+        commands
+            .spawn((
+                Name::new("Environment"),
+                EnvironmentMarker,
+                PlayingScene,
+                SceneRoot(scene_handle),
+                Transform {
+                    translation: Vec3::new(0.0, -1.5, 0.0),
+                    rotation: Quat::from_rotation_y(-core::f32::consts::PI * 0.5),
+                    scale: Vec3::splat(0.05), // ⬅️ scale environment down
+                },
+                ColliderConstructorHierarchy::new(ColliderConstructor::TrimeshFromMesh),
+                RigidBody::Static,
+                DebugRender::default(),
+            ))
+            .observe(|_: Trigger<SceneInstanceReady>, mut build: ResMut<LevelBuild>| {
+                build.scene_spawned = true;
+            })
+            .observe(
+                |_: Trigger<ColliderConstructorHierarchyReady>, mut build: ResMut<LevelBuild>| {
+                    build.colliders_ready = true;
+                },
+            );
 
         // Light
         commands.spawn((
@@ -89,53 +158,42 @@ impl PlayingScene {
                 std::f32::consts::FRAC_PI_4,
                 0.0,
             )),
+            PlayingScene,
         ));
-    }
 
-    fn check_environment_loaded(
-        time: Res<Time>,
-        mut timer: ResMut<EnvironmentLoadTimer>,
-        commands: Commands,
-        assets: Res<AssetServer>,
-        environment_query: Query<&EnvironmentMarker>,
-        collider_query: Query<&Collider>,
-    ) {
-        timer.timer.tick(time.delta());
-        
-        // Check if environment exists and has colliders
-        if timer.timer.just_finished() {
-            if let Ok(_) = environment_query.single() {
-                // Check if any colliders have been generated
-                if !collider_query.is_empty() {
-                    info!("Environment and colliders loaded, spawning player");
-                    Self::spawn_player(commands, assets);
-                } else {
-                    info!("Waiting for colliders to be generated...");
-                    // Reset timer if colliders aren't ready
-                    timer.timer.reset();
-                }
-            } else {
-                info!("Waiting for environment to be loaded...");
-                // Reset timer if environment isn't loaded
-                timer.timer.reset();
-            }
-        }
+        // Map camera and HUD
+        spawn_minimap(&mut commands, &mut images, PlayingScene);
     }
 
-    fn spawn_player(mut commands: Commands, assets: Res<AssetServer>) {
+    /// Spawns the player and its camera into the finished level
+    fn spawn_player(mut commands: Commands, assets: Res<AssetServer>, settings: Res<GameSettings>) {
         // Player
         commands.spawn((
             Name::new("Survivor Character"),
             Player,
-            CharacterControllerBundle::new(Collider::capsule(0.5, 0.8)).with_movement(
-                112.5,  // acceleration
-                0.9,    // damping
-                16.0,   // jump impulse (increased further)
-            ),
+            PlayingScene,
+            Health {
+                current: 100,
+                max: 100,
+            },
+            Stamina::default(),
+            FallDamage::default(),
+            CharacterControllerBundle::new(Collider::capsule(0.5, 0.8))
+                .with_movement(
+                    112.5,  // acceleration
+                    0.9,    // damping
+                    16.0,   // jump impulse (increased further)
+                )
+                .with_control_scheme(settings.control_scheme)
+                .with_max_slope_angle(40.0_f32.to_radians())
+                .with_step_height(1.5), // the collider is scaled up with the model
             Friction::ZERO.with_combine_rule(CoefficientCombine::Min),
             Restitution::ZERO.with_combine_rule(CoefficientCombine::Min),
             GravityScale(2.0),
+            // The controller moves in fixed steps, so smooth the rendered transform
+            TransformInterpolation,
             SceneRoot(assets.load("models/person.glb#Scene0")),
+            LocomotionAnimations::new(assets.load("models/person.glb")),
             Transform {
                 translation: Vec3::new(10.0, 5.0, -60.0),
                 scale: Vec3::splat(5.0),
@@ -144,14 +202,48 @@ impl PlayingScene {
         ));
 
         // Camera
+        let camera_transform =
+            Transform::from_xyz(0.0, 5.0, -8.0).looking_at(Vec3::new(0.0, 3.5, 0.0), Vec3::Y);
         commands.spawn((
             Camera3d::default(),
             Camera {
                 order: 1,
                 ..default()
             },
-            Transform::from_xyz(0.0, 5.0, -8.0).looking_at(Vec3::new(0.0, 3.5, 0.0), Vec3::Y),
+            camera_transform,
+            CameraRig::from(camera_transform),
             FlyCam::default(),
+            CameraCollision::default(),
+            CameraShake::default(),
+            // Fly over the level before handing the camera to the player
+            PlayCinematic(assets.load("cinematics/level_intro.cinematic.ron")),
+            PlayingScene,
         ));
     }
+
+    /// Records the statistics shown when the run ends
+    fn track_run(
+        time: Res<Time>,
+        mut summary: ResMut<RunSummary>,
+        mut damage_event_reader: EventReader<PlayerDamaged>,
+    ) {
+        summary.time_survived += time.delta_secs();
+        for damage in damage_event_reader.read() {
+            summary.damage_taken += damage.amount;
+            if damage.source == DamageSource::Fall {
+                summary.hard_landings += 1;
+            }
+        }
+    }
+
+    /// Ends the run once the player runs out of health
+    fn check_player_defeated(
+        players: Query<&Health, (With<Player>, Changed<Health>)>,
+        mut requests: EventWriter<RequestScreen>,
+    ) {
+        if players.iter().any(|health| health.current == 0) {
+            info!("Player defeated");
+            requests.write(RequestScreen(Screen::GameOver));
+        }
+    }
 }
@@ -1,18 +1,31 @@
-use bevy::{prelude::*, render::view::RenderLayers, sprite::Anchor};
+use bevy::{
+    asset::{LoadState, RecursiveDependencyLoadState},
+    prelude::*,
+    sprite::Anchor,
+};
 use bevy_lunex::*;
 
 use super::Screen;
-use crate::game::resources::MainTrack;
+use super::transition::RequestScreen;
+use crate::game::resources::{MainTrack, RunSummary};
 use crate::ui::styles::ElysiumDescentColorPalette;
 
 // ===== PLUGIN SETUP =====
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_resource::<MainTrack>();
+    app.init_resource::<MainTrack>()
+        .init_resource::<LevelProgress>()
+        .init_resource::<LevelBuild>();
 
     app.add_systems(
         OnEnter(Screen::GeneratingLevel),
-        GeneratingLevelScene::spawn,
+        (GeneratingLevelScene::start, GeneratingLevelScene::spawn).chain(),
+    )
+    .add_systems(
+        Update,
+        (GeneratingLevelScene::track_progress, GeneratingLevelScene::update_progress_bar)
+            .chain()
+            .run_if(in_state(Screen::GeneratingLevel)),
     )
     .add_systems(
         OnExit(Screen::GeneratingLevel),
@@ -33,14 +46,205 @@ fn despawn_scene<S: Component>(mut commands: Commands, query: Query<Entity, With
 #[derive(Component)]
 struct GeneratingLevelScene;
 
+/// How far the level build has come
+#[derive(Resource, Default, Debug)]
+pub struct LevelProgress {
+    /// What is currently being built
+    pub stage: String,
+    /// Progress between 0 and 1
+    pub fraction: f32,
+}
+
+/// Everything the level needs before gameplay can start. The gameplay scene
+/// fills this in as it builds the level, and keeps the tracked assets alive.
+#[derive(Resource, Default)]
+pub struct LevelBuild {
+    assets: Vec<UntypedHandle>,
+    /// The environment scene has been spawned into the world
+    pub scene_spawned: bool,
+    /// Colliders have been generated for the whole environment
+    pub colliders_ready: bool,
+}
+
+impl LevelBuild {
+    /// Share of the progress bar for loading assets, the rest is for building
+    const ASSET_SHARE: f32 = 0.7;
+
+    pub fn new(assets: impl IntoIterator<Item = UntypedHandle>) -> Self {
+        Self {
+            assets: assets.into_iter().collect(),
+            ..default()
+        }
+    }
+
+    /// The current stage and overall progress, or an error if an asset failed to load
+    fn progress(&self, asset_server: &AssetServer) -> Result<(String, f32), String> {
+        let mut loaded = 0.0;
+        for handle in &self.assets {
+            loaded += match asset_server.get_load_states(handle.id()) {
+                Some((_, _, RecursiveDependencyLoadState::Loaded)) => 1.0,
+                Some((LoadState::Failed(error), ..)) => return Err(error.to_string()),
+                Some((_, _, RecursiveDependencyLoadState::Failed(error))) => {
+                    return Err(error.to_string());
+                }
+                // The asset is in, its dependencies aren't yet
+                Some((LoadState::Loaded, ..)) => 0.5,
+                _ => 0.0,
+            };
+        }
+
+        let total = self.assets.len().max(1) as f32;
+        let assets = loaded / total;
+        if assets < 1.0 {
+            let done = self
+                .assets
+                .iter()
+                .filter(|handle| asset_server.is_loaded_with_dependencies(handle.id()));
+            let stage = format!("Loading assets ({}/{})", done.count(), self.assets.len());
+            return Ok((stage, assets * Self::ASSET_SHARE));
+        }
+
+        let build_share = (1.0 - Self::ASSET_SHARE) / 2.0;
+        Ok(match (self.scene_spawned, self.colliders_ready) {
+            (false, _) => ("Building environment".to_string(), Self::ASSET_SHARE),
+            (true, false) => (
+                "Generating colliders".to_string(),
+                Self::ASSET_SHARE + build_share,
+            ),
+            (true, true) => ("Ready".to_string(), 1.0),
+        })
+    }
+
+    fn is_ready(&self, asset_server: &AssetServer) -> bool {
+        self.scene_spawned
+            && self.colliders_ready
+            && self
+                .assets
+                .iter()
+                .all(|handle| asset_server.is_loaded_with_dependencies(handle.id()))
+    }
+}
+
+/// The filled part of the progress bar
+#[derive(Component)]
+struct ProgressFill;
+
+/// The text describing the current stage
+#[derive(Component)]
+struct ProgressStage;
+
 // ===== GENERATING LEVEL IMPLEMENTATION =====
 
 impl GeneratingLevelScene {
-    fn spawn(
-        mut commands: Commands,
+    /// Starts a fresh run. The gameplay scene starts building the level.
+    fn start(mut commands: Commands) {
+        commands.insert_resource(RunSummary::default());
+        commands.insert_resource(LevelProgress {
+            stage: "Loading assets".to_string(),
+            fraction: 0.0,
+        });
+    }
+
+    fn spawn(mut commands: Commands, asset_server: Res<AssetServer>, progress: Res<LevelProgress>) {
+        // Create UI
+        commands.spawn((
+            UiLayoutRoot::new_2d(),
+            // Make the UI synchronized with camera viewport size
+            UiFetchFromCamera::<0>,
+            // A scene marker for later mass scene despawn, not UI related
+            GeneratingLevelScene
+        )).with_children(|ui| {
+
+            // Spawn the background
+            ui.spawn((
+                Name::new("Background"),
+                UiLayout::solid().size((1920.0, 1080.0)).scaling(Scaling::Fill).pack(),
+                Sprite::from_image(asset_server.load("images/ui/background.png")),
+            ));
+
+            // Spawn the stage text
+            ui.spawn((
+                UiLayout::window().pos(Rl((50.0, 74.0))).anchor(Anchor::BottomCenter).pack(),
+                UiColor::from(Color::ELYSIUM_DESCENT_RED),
+                UiTextSize::from(Rh(4.0)),
+                Text2d::new(progress.stage.to_ascii_uppercase()),
+                TextFont {
+                    font: asset_server.load("fonts/rajdhani/Rajdhani-Medium.ttf"),
+                    font_size: 64.0,
+                    ..default()
+                },
+                ProgressStage,
+                Pickable::IGNORE,
+            ));
+
+            // Spawn the progress bar
+            ui.spawn((
+                Name::new("Progress Bar"),
+                UiLayout::window().pos(Rl((20.0, 76.0))).size(Rl((60.0, 3.0))).pack(),
+                Sprite {
+                    image: asset_server.load("images/ui/components/button_symetric_sliced.png"),
+                    image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::all(32.0), ..default() }),
+                    ..default()
+                },
+                UiColor::from(Color::ELYSIUM_DESCENT_RED.with_alpha(0.15)),
+                Pickable::IGNORE,
+            )).with_children(|ui| {
+
+                // Spawn the fill
+                ui.spawn((
+                    UiLayout::window().size(Rl((0.0, 100.0))).pack(),
+                    Sprite::default(),
+                    UiColor::from(Color::ELYSIUM_DESCENT_YELLOW),
+                    ProgressFill,
+                    Pickable::IGNORE,
+                ));
+            });
+        });
+    }
+
+    /// Follows the level build and moves on to gameplay once everything is ready
+    fn track_progress(
         asset_server: Res<AssetServer>,
-        mut images: ResMut<Assets<Image>>,
-        mut materials: ResMut<Assets<StandardMaterial>>,
+        build: Res<LevelBuild>,
+        mut progress: ResMut<LevelProgress>,
+        mut requests: EventWriter<RequestScreen>,
+    ) {
+        match build.progress(&asset_server) {
+            Ok((stage, fraction)) => {
+                if progress.stage != stage {
+                    info!("Level build: {stage}");
+                    progress.stage = stage;
+                }
+                progress.fraction = fraction;
+            }
+            Err(error) => {
+                error!("Failed to load the level: {error}");
+                requests.write(RequestScreen(Screen::MainMenu));
+                return;
+            }
+        }
+
+        if build.is_ready(&asset_server) {
+            requests.write(RequestScreen(Screen::GamePlay));
+        }
+    }
+
+    /// Shows the current progress on the loading UI
+    fn update_progress_bar(
+        time: Res<Time>,
+        progress: Res<LevelProgress>,
+        mut fills: Query<&mut UiLayout, With<ProgressFill>>,
+        mut stages: Query<&mut Text2d, With<ProgressStage>>,
+        mut shown: Local<f32>,
     ) {
+        // Ease the bar so it doesn't jump between stages, starting empty on a new build
+        *shown = shown.min(progress.fraction);
+        *shown = shown.lerp(progress.fraction, (8.0 * time.delta_secs()).min(1.0));
+        for mut layout in &mut fills {
+            *layout = UiLayout::window().size(Rl((*shown * 100.0, 100.0))).pack();
+        }
+        for mut text in &mut stages {
+            text.0 = progress.stage.to_ascii_uppercase();
+        }
     }
 }
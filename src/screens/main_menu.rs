@@ -5,6 +5,7 @@ use bevy_lunex::*;
 use vleue_kinetoscope::*;
 
 use super::Screen;
+use super::transition::RequestScreen;
 use crate::game::resources::MainTrack;
 use crate::systems::movie::*;
 use crate::ui::styles::ElysiumDescentColorPalette;
@@ -129,8 +130,8 @@ impl IntroScene {
                         .playback(MoviePlayback::Stop),
                 ))
                 .observe(
-                    |_event: Trigger<MovieEnded>, mut next: ResMut<NextState<Screen>>| {
-                        next.set(Screen::MainMenu)
+                    |_event: Trigger<MovieEnded>, mut requests: EventWriter<RequestScreen>| {
+                        requests.write(RequestScreen(Screen::MainMenu));
                     },
                 );
 
@@ -196,9 +197,9 @@ impl IntroScene {
                 .observe(hover_set::<Pointer<Over>, true>)
                 .observe(hover_set::<Pointer<Out>, false>)
                 .observe(
-                    |_: Trigger<Pointer<Click>>, mut next: ResMut<NextState<Screen>>| {
+                    |_: Trigger<Pointer<Click>>, mut requests: EventWriter<RequestScreen>| {
                         info!("Skip button clicked!");
-                        next.set(Screen::MainMenu);
+                        requests.write(RequestScreen(Screen::MainMenu));
                     },
                 );
             });
@@ -348,21 +349,21 @@ impl MainMenuScene {
                                 // Assign a functionality to the buttons
                                 match button {
                                     "New Game" => {
-                                        button_entity.observe(|_: Trigger<Pointer<Click>>, mut next: ResMut<NextState<Screen>>| {
+                                        button_entity.observe(|_: Trigger<Pointer<Click>>, mut requests: EventWriter<RequestScreen>| {
                                             // Change the state to settings
-                                            next.set(Screen::NewGame);
+                                            requests.write(RequestScreen(Screen::NewGame));
                                         });
                                     },
                                     "Settings" => {
-                                        button_entity.observe(|_: Trigger<Pointer<Click>>, mut next: ResMut<NextState<Screen>>| {
+                                        button_entity.observe(|_: Trigger<Pointer<Click>>, mut requests: EventWriter<RequestScreen>| {
                                             // Change the state to settings
-                                            next.set(Screen::Settings);
+                                            requests.write(RequestScreen(Screen::Settings));
                                         });
                                     },
                                     "Continue" => {
-                                        button_entity.observe(|_: Trigger<Pointer<Click>>, mut next: ResMut<NextState<Screen>>| {
+                                        button_entity.observe(|_: Trigger<Pointer<Click>>, mut requests: EventWriter<RequestScreen>| {
                                             // Change the state to settings
-                                            next.set(Screen::GamePlay);
+                                            requests.write(RequestScreen(Screen::GeneratingLevel));
                                         });
                                     },
                                     "Quit Game" => {
@@ -1,18 +1,27 @@
+pub mod game_over;
 pub mod gameplay;
+pub mod generating_level;
 pub mod main_menu;
 pub mod new_game;
+pub mod pause_menu;
 pub mod settings;
+pub mod transition;
 
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
+    app.add_computed_state::<InRun>();
 
     app.add_plugins((
         main_menu::plugin,
         settings::plugin,
         new_game::plugin,
         gameplay::plugin,
+        pause_menu::plugin,
+        game_over::plugin,
+        generating_level::plugin,
+        transition::plugin,
     ));
 }
 
@@ -25,4 +34,32 @@ pub(crate) enum Screen {
     NewGame,
     Settings,
     Inventory,
+    Dungeon,
+    GeneratingLevel,
+    Pause,
+    GameOver,
+}
+
+/// Present on every screen that belongs to a run, from generating the level
+/// until it's left for the main menu or game over. The gameplay world lives
+/// as long as this state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct InRun;
+
+impl ComputedStates for InRun {
+    type SourceStates = Screen;
+
+    fn compute(screen: Screen) -> Option<Self> {
+        match screen {
+            // Settings can be opened from the pause menu. Opened from the main
+            // menu there is no world, so leaving it again has nothing to clean up.
+            Screen::GeneratingLevel
+            | Screen::GamePlay
+            | Screen::Pause
+            | Screen::Inventory
+            | Screen::Dungeon
+            | Screen::Settings => Some(InRun),
+            Screen::Intro | Screen::MainMenu | Screen::NewGame | Screen::GameOver => None,
+        }
+    }
 }
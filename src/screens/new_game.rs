@@ -2,8 +2,10 @@ use bevy::{prelude::*, render::view::RenderLayers, sprite::Anchor};
 use bevy_lunex::*;
 
 use super::Screen;
+use super::transition::RequestScreen;
 use crate::game::resources::MainTrack;
-use crate::rendering::cameras::showcase::{ShowcaseCamera, ShowcaseCameraPlugin};
+use crate::rendering::cameras::camera_shake::CameraShake;
+use crate::rendering::cameras::showcase::{ShowcaseCamera, ShowcaseCameraPlugin, ShowcaseFocus};
 use crate::ui::styles::ElysiumDescentColorPalette;
 
 // ===== PLUGIN SETUP =====
@@ -43,6 +45,7 @@ impl NewGameScene {
         // Spawn the 3D camera that will render to the texture
         commands.spawn((
             ShowcaseCamera::default(),
+            CameraShake::subtle(),
             Camera3d::default(),
             Camera::clear_render_to(image_handle.clone()).with_order(-1),
             // Set the render layers to be Default + 3D UI Debug for gizmos
@@ -155,7 +158,68 @@ impl NewGameScene {
 
             // Enable the transition on hover
             }).observe(hover_set::<Pointer<Over>, true>).observe(hover_set::<Pointer<Out>, false>)
-            .observe(|_: Trigger<Pointer<Click>>, mut next: ResMut<NextState<Screen>>| next.set(Screen::MainMenu) );
+            .observe(|_: Trigger<Pointer<Click>>, mut requests: EventWriter<RequestScreen>| {
+                requests.write(RequestScreen(Screen::MainMenu));
+            });
+
+            // Spawn the camera focus buttons
+            let gap = 2.0;
+            let size = 6.0;
+            let mut offset = 16.0;
+            for focus in ShowcaseFocus::ALL {
+                ui.spawn((
+                    Name::new(focus.label()),
+                    UiLayout::window().pos(Rl((2.0, offset))).size(Rl((12.0, size))).pack(),
+                    OnHoverSetCursor::new(bevy::window::SystemCursorIcon::Pointer),
+                )).with_children(|ui| {
+                    // Spawn the image
+                    ui.spawn((
+                        UiLayout::new(vec![
+                            (UiBase::id(), UiLayout::boundary().pos2(Rl(100.0)).wrap()),
+                            (UiHover::id(), UiLayout::boundary().pos2(Rl(100.0)).x2(Rl(115.0)).wrap())
+                        ]),
+                        UiHover::new().forward_speed(20.0).backward_speed(4.0),
+                        UiColor::new(vec![
+                            (UiBase::id(), Color::ELYSIUM_DESCENT_RED.with_alpha(0.15)),
+                            (UiHover::id(), Color::ELYSIUM_DESCENT_YELLOW.with_alpha(1.2))
+                        ]),
+                        Sprite {
+                            image: asset_server.load("images/ui/components/button_sliced_bottom_right.png"),
+                            image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::all(32.0), ..default() }),
+                            ..default()
+                        },
+                        Pickable::IGNORE,
+                    )).with_children(|ui| {
+
+                        // Spawn the text
+                        ui.spawn((
+                            UiLayout::window().pos((Rh(40.0), Rl(50.0))).anchor(Anchor::CenterLeft).pack(),
+                            UiColor::new(vec![
+                                (UiBase::id(), Color::ELYSIUM_DESCENT_RED),
+                                (UiHover::id(), Color::ELYSIUM_DESCENT_YELLOW.with_alpha(1.2))
+                            ]),
+                            UiHover::new().forward_speed(20.0).backward_speed(4.0),
+                            UiTextSize::from(Rh(60.0)),
+                            Text2d::new(focus.label()),
+                            TextFont {
+                                font: asset_server.load("fonts/rajdhani/Rajdhani-Medium.ttf"),
+                                font_size: 64.0,
+                                ..default()
+                            },
+                            Pickable::IGNORE,
+                        ));
+                    });
+
+                // Move the preview camera to the preset on click
+                }).observe(hover_set::<Pointer<Over>, true>).observe(hover_set::<Pointer<Out>, false>)
+                .observe(move |_: Trigger<Pointer<Click>>, mut cameras: Query<&mut ShowcaseCamera>| {
+                    for mut camera in &mut cameras {
+                        camera.focus(focus);
+                    }
+                });
+
+                offset += gap + size;
+            }
 
             // Spawn panel boundary
             ui.spawn((
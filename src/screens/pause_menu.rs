@@ -1,7 +1,9 @@
-use bevy::{prelude::*, render::view::RenderLayers, sprite::Anchor};
+use bevy::{prelude::*, sprite::Anchor};
 use bevy_lunex::*;
 
 use super::Screen;
+use super::transition::RequestScreen;
+use super::settings::SettingsReturn;
 use crate::game::resources::MainTrack;
 use crate::ui::styles::ElysiumDescentColorPalette;
 
@@ -29,11 +31,122 @@ struct PauseScene;
 // ===== PAUSE SCENE IMPLEMENTATION =====
 
 impl PauseScene {
-    fn spawn(
-        mut commands: Commands,
-        asset_server: Res<AssetServer>,
-        mut images: ResMut<Assets<Image>>,
-        mut materials: ResMut<Assets<StandardMaterial>>,
-    ) {
+    fn spawn(mut commands: Commands, asset_server: Res<AssetServer>) {
+        // Create UI
+        commands.spawn((
+            UiLayoutRoot::new_2d(),
+            // Make the UI synchronized with camera viewport size
+            UiFetchFromCamera::<0>,
+            // A scene marker for later mass scene despawn, not UI related
+            PauseScene
+        )).with_children(|ui| {
+
+            // Spawn the dimmed backdrop
+            ui.spawn((
+                Name::new("Backdrop"),
+                UiLayout::window().full().pack(),
+                Sprite::default(),
+                UiColor::from(Color::BLACK.with_alpha(0.6)),
+            ));
+
+            // Spawn the title
+            ui.spawn((
+                UiLayout::window().pos(Rl((50.0, 28.0))).anchor(Anchor::Center).pack(),
+                UiColor::from(Color::ELYSIUM_DESCENT_RED),
+                UiTextSize::from(Rh(9.0)),
+                Text2d::new("PAUSED"),
+                TextFont {
+                    font: asset_server.load("fonts/rajdhani/Rajdhani-SemiBold.ttf"),
+                    font_size: 64.0,
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ));
+
+            // Spawn button boundary
+            ui.spawn((
+                Name::new("Button List"),
+                UiLayout::window().pos(Rl((50.0, 38.0))).anchor(Anchor::TopCenter).size(Rl((24.0, 30.0))).pack(),
+            )).with_children(|ui| {
+
+                // Spawn buttons
+                let gap = 4.0;
+                let size = 20.0;
+                let mut offset = 0.0;
+                for button in ["Resume", "Settings", "Quit to menu"] {
+
+                    // Spawn the button
+                    let mut button_entity = ui.spawn((
+                        Name::new(button),
+                        UiLayout::window().y(Rl(offset)).size(Rl((100.0, size))).pack(),
+                        OnHoverSetCursor::new(bevy::window::SystemCursorIcon::Pointer),
+                    ));
+                    button_entity.with_children(|ui| {
+                        // Spawn the image
+                        ui.spawn((
+                            UiLayout::new(vec![
+                                (UiBase::id(), UiLayout::window().full()),
+                                (UiHover::id(), UiLayout::window().x(Rl(5.0)).full())
+                            ]),
+                            UiHover::new().forward_speed(20.0).backward_speed(4.0),
+                            UiColor::new(vec![
+                                (UiBase::id(), Color::ELYSIUM_DESCENT_RED.with_alpha(0.15)),
+                                (UiHover::id(), Color::ELYSIUM_DESCENT_YELLOW.with_alpha(1.2))
+                            ]),
+                            Sprite {
+                                image: asset_server.load("images/ui/components/button_symetric_sliced.png"),
+                                image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::all(32.0), ..default() }),
+                                ..default()
+                            },
+                            Pickable::IGNORE,
+                        )).with_children(|ui| {
+
+                            // Spawn the text
+                            ui.spawn((
+                                UiLayout::window().pos((Rh(40.0), Rl(50.0))).anchor(Anchor::CenterLeft).pack(),
+                                UiColor::new(vec![
+                                    (UiBase::id(), Color::ELYSIUM_DESCENT_RED),
+                                    (UiHover::id(), Color::ELYSIUM_DESCENT_YELLOW.with_alpha(1.2))
+                                ]),
+                                UiHover::new().forward_speed(20.0).backward_speed(4.0),
+                                UiTextSize::from(Rh(60.0)),
+                                Text2d::new(button),
+                                TextFont {
+                                    font: asset_server.load("fonts/rajdhani/Rajdhani-Medium.ttf"),
+                                    font_size: 64.0,
+                                    ..default()
+                                },
+                                Pickable::IGNORE,
+                            ));
+                        });
+
+                    // Enable the transition on hover
+                    }).observe(hover_set::<Pointer<Over>, true>).observe(hover_set::<Pointer<Out>, false>);
+
+                    // Assign a functionality to the buttons
+                    match button {
+                        "Resume" => {
+                            button_entity.observe(|_: Trigger<Pointer<Click>>, mut requests: EventWriter<RequestScreen>| {
+                                requests.write(RequestScreen(Screen::GamePlay));
+                            });
+                        },
+                        "Settings" => {
+                            button_entity.observe(|_: Trigger<Pointer<Click>>, mut requests: EventWriter<RequestScreen>, mut settings_return: ResMut<SettingsReturn>| {
+                                // Come back here when leaving the settings
+                                settings_return.0 = Screen::Pause;
+                                requests.write(RequestScreen(Screen::Settings));
+                            });
+                        },
+                        _ => {
+                            button_entity.observe(|_: Trigger<Pointer<Click>>, mut requests: EventWriter<RequestScreen>| {
+                                requests.write(RequestScreen(Screen::MainMenu));
+                            });
+                        },
+                    }
+
+                    offset += gap + size;
+                }
+            });
+        });
     }
 }
@@ -2,15 +2,23 @@ use bevy::{prelude::*, render::view::RenderLayers, sprite::Anchor};
 use bevy_lunex::*;
 
 use super::Screen;
-use crate::game::resources::MainTrack;
+use crate::game::resources::{GameSettings, MainTrack};
+use crate::rendering::cameras::camera_mode::CameraMode;
+use crate::ControlScheme;
 use crate::ui::styles::ElysiumDescentColorPalette;
 
 // ===== PLUGIN SETUP =====
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Settings), SettingsScene::spawn)
-        .add_systems(OnExit(Screen::Settings), despawn_scene::<SettingsScene>)
-        .init_resource::<MainTrack>();
+        .add_systems(OnExit(Screen::Settings), (despawn_scene::<SettingsScene>, reset_return))
+        .add_systems(
+            Update,
+            SettingsScene::update_values
+                .run_if(in_state(Screen::Settings).and(resource_changed::<GameSettings>)),
+        )
+        .init_resource::<MainTrack>()
+        .init_resource::<SettingsReturn>();
 }
 
 // ===== SYSTEMS =====
@@ -21,11 +29,86 @@ fn despawn_scene<S: Component>(mut commands: Commands, query: Query<Entity, With
     }
 }
 
+/// Opening the settings from elsewhere has to ask again to be returned there
+fn reset_return(mut settings_return: ResMut<SettingsReturn>) {
+    settings_return.0 = Screen::MainMenu;
+}
+
 // ===== RESOURCES & COMPONENTS =====
 
+/// The screen to go back to when leaving the settings
+#[derive(Resource, Debug)]
+pub(crate) struct SettingsReturn(pub Screen);
+
+impl Default for SettingsReturn {
+    fn default() -> Self {
+        Self(Screen::MainMenu)
+    }
+}
+
 #[derive(Component)]
 struct SettingsScene;
 
+/// An option that can be changed on the settings screen
+#[derive(Component, Clone, Copy, Debug)]
+enum SettingsOption {
+    ControlScheme,
+    CameraMode,
+    CameraShake,
+}
+
+impl SettingsOption {
+    const ALL: [SettingsOption; 3] = [
+        SettingsOption::ControlScheme,
+        SettingsOption::CameraMode,
+        SettingsOption::CameraShake,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            SettingsOption::ControlScheme => "Movement",
+            SettingsOption::CameraMode => "Camera",
+            SettingsOption::CameraShake => "Camera shake",
+        }
+    }
+
+    fn value(self, settings: &GameSettings) -> &'static str {
+        match self {
+            SettingsOption::ControlScheme => match settings.control_scheme {
+                ControlScheme::Tank => "Tank",
+                ControlScheme::CameraRelative => "Camera relative",
+            },
+            SettingsOption::CameraMode => match settings.camera_mode {
+                CameraMode::ThirdPerson => "Third person",
+                CameraMode::TopDown => "Top-down",
+                CameraMode::FreeDebug => "Free (debug)",
+            },
+            SettingsOption::CameraShake => match settings.camera_shake {
+                true => "On",
+                false => "Off",
+            },
+        }
+    }
+
+    /// Switches the option to its next value
+    fn cycle(self, settings: &mut GameSettings) {
+        match self {
+            SettingsOption::ControlScheme => {
+                settings.control_scheme = match settings.control_scheme {
+                    ControlScheme::Tank => ControlScheme::CameraRelative,
+                    ControlScheme::CameraRelative => ControlScheme::Tank,
+                };
+            }
+            SettingsOption::CameraMode => settings.camera_mode = settings.camera_mode.next(),
+            SettingsOption::CameraShake => settings.camera_shake = !settings.camera_shake,
+        }
+    }
+}
+
+/// Marks the text displaying the current value of a [`SettingsOption`]
+#[derive(Component)]
+struct SettingsValue(SettingsOption);
+
 // ===== SETTINGS SCENE IMPLEMENTATION =====
 
 impl SettingsScene {
@@ -33,6 +116,7 @@ impl SettingsScene {
         mut commands: Commands,
         asset_server: Res<AssetServer>,
         mut images: ResMut<Assets<Image>>,
+        settings: Res<GameSettings>,
     ) {
         // Create the transparent render texture
         let image_handle = images.add(Image::clear_render_texture());
@@ -157,13 +241,95 @@ impl SettingsScene {
 
                 });
 
-                // Spawn the Bevy UI embedd
+                // Spawn the Bevy UI embedd, below the options list
                 ui.spawn((
-                    UiLayout::boundary().y1(Rl(10.0)).pos2(Rl(100.0)).pack(),
+                    UiLayout::boundary().y1(Rl(58.0)).pos2(Rl(100.0)).pack(),
                     Sprite::from_image(image_handle),
                     UiEmbedding,
                 ));
 
+                // Spawn the options list
+                ui.spawn((
+                    Name::new("Options List"),
+                    UiLayout::window().pos(Rl((10.0, 14.0))).size(Rl((80.0, 40.0))).pack(),
+                )).with_children(|ui| {
+
+                    let gap = 5.0;
+                    let size = 20.0;
+                    let mut offset = 0.0;
+                    for option in SettingsOption::ALL {
+
+                        // Spawn the option row
+                        ui.spawn((
+                            Name::new(option.label()),
+                            UiLayout::window().y(Rl(offset)).size(Rl((100.0, size))).pack(),
+                            OnHoverSetCursor::new(bevy::window::SystemCursorIcon::Pointer),
+                        )).with_children(|ui| {
+
+                            // Spawn the background
+                            ui.spawn((
+                                UiLayout::window().full().pack(),
+                                UiHover::new().forward_speed(20.0).backward_speed(4.0),
+                                UiColor::new(vec![
+                                    (UiBase::id(), Color::ELYSIUM_DESCENT_RED.with_alpha(0.15)),
+                                    (UiHover::id(), Color::ELYSIUM_DESCENT_YELLOW.with_alpha(1.2))
+                                ]),
+                                Sprite {
+                                    image: asset_server.load("images/ui/components/button_symetric_sliced.png"),
+                                    image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::all(32.0), ..default() }),
+                                    ..default()
+                                },
+                                Pickable::IGNORE,
+                            )).with_children(|ui| {
+
+                                // Spawn the label
+                                ui.spawn((
+                                    UiLayout::window().pos((Rh(40.0), Rl(50.0))).anchor(Anchor::CenterLeft).pack(),
+                                    UiColor::new(vec![
+                                        (UiBase::id(), Color::ELYSIUM_DESCENT_RED),
+                                        (UiHover::id(), Color::ELYSIUM_DESCENT_YELLOW.with_alpha(1.2))
+                                    ]),
+                                    UiHover::new().forward_speed(20.0).backward_speed(4.0),
+                                    UiTextSize::from(Rh(60.0)),
+                                    Text2d::new(option.label().to_ascii_uppercase()),
+                                    TextFont {
+                                        font: asset_server.load("fonts/rajdhani/Rajdhani-Medium.ttf"),
+                                        font_size: 64.0,
+                                        ..default()
+                                    },
+                                    Pickable::IGNORE,
+                                ));
+
+                                // Spawn the value
+                                ui.spawn((
+                                    UiLayout::window().pos(Rl((95.0, 50.0))).anchor(Anchor::CenterRight).pack(),
+                                    UiColor::new(vec![
+                                        (UiBase::id(), Color::ELYSIUM_DESCENT_BLUE),
+                                        (UiHover::id(), Color::ELYSIUM_DESCENT_YELLOW.with_alpha(1.2))
+                                    ]),
+                                    UiHover::new().forward_speed(20.0).backward_speed(4.0),
+                                    UiTextSize::from(Rh(60.0)),
+                                    Text2d::new(option.value(&settings)),
+                                    TextFont {
+                                        font: asset_server.load("fonts/rajdhani/Rajdhani-Bold.ttf"),
+                                        font_size: 64.0,
+                                        ..default()
+                                    },
+                                    SettingsValue(option),
+                                    Pickable::IGNORE,
+                                ));
+                            });
+
+                        // Cycle the value on click
+                        }).observe(hover_set::<Pointer<Over>, true>).observe(hover_set::<Pointer<Out>, false>)
+                        .observe(move |_: Trigger<Pointer<Click>>, mut settings: ResMut<GameSettings>| {
+                            option.cycle(&mut settings);
+                        });
+
+                        offset += gap + size;
+                    }
+                });
+
             });
         });
 
@@ -195,4 +361,11 @@ impl SettingsScene {
                 ));
             });
     }
+
+    /// Refreshes the displayed option values after the settings change
+    fn update_values(settings: Res<GameSettings>, mut values: Query<(&SettingsValue, &mut Text2d)>) {
+        for (value, mut text) in &mut values {
+            text.0 = value.0.value(&settings).to_string();
+        }
+    }
 }
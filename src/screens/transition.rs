@@ -0,0 +1,241 @@
+use bevy::prelude::*;
+use bevy_lunex::*;
+use std::time::Duration;
+
+use super::Screen;
+
+// ===== PLUGIN SETUP =====
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ScreenFade>()
+        .init_resource::<ScreenTransition>()
+        .add_event::<RequestScreen>()
+        .add_event::<ScreenFadeStarted>()
+        .add_systems(Startup, FadeOverlay::spawn)
+        .add_systems(
+            Last,
+            (
+                ScreenTransition::update,
+                FadeOverlay::update.run_if(
+                    resource_changed::<ScreenTransition>.or(resource_changed::<ScreenFade>),
+                ),
+            )
+                .chain(),
+        );
+}
+
+// ===== RESOURCES & COMPONENTS =====
+
+/// How screen changes fade out and back in
+#[derive(Resource, Clone, Debug)]
+pub struct ScreenFade {
+    /// Seconds for each half of the fade, zero cuts straight to the next screen
+    pub duration: f32,
+    pub color: Color,
+    pub easing: EaseFunction,
+    pub style: FadeStyle,
+}
+
+impl Default for ScreenFade {
+    fn default() -> Self {
+        Self {
+            duration: 0.4,
+            color: Color::BLACK,
+            easing: EaseFunction::CubicInOut,
+            style: FadeStyle::Fade,
+        }
+    }
+}
+
+/// How the overlay covers the screen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FadeStyle {
+    /// Blends the whole screen to the fade color
+    #[default]
+    Fade,
+    /// Sweeps a solid panel across from left to right
+    Wipe,
+}
+
+impl ScreenFade {
+    /// Time each half of the fade takes
+    pub fn half(&self) -> Duration {
+        Duration::from_secs_f32(self.duration.max(0.0))
+    }
+}
+
+/// Asks to change screen. Screens are always changed through this event rather
+/// than `NextState<Screen>`, so the fade can cover the change wherever it was
+/// requested from.
+#[derive(Event, Debug, Clone)]
+pub struct RequestScreen(pub Screen);
+
+/// Sent when the screen starts fading out towards a new screen
+#[derive(Event, Debug)]
+pub struct ScreenFadeStarted {
+    pub to: Screen,
+}
+
+/// The screen change in progress, if any
+#[derive(Resource, Default, Debug)]
+pub struct ScreenTransition {
+    phase: FadePhase,
+    /// Seconds into the current phase
+    elapsed: f32,
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+enum FadePhase {
+    #[default]
+    Idle,
+    /// Covering the screen before switching to the target
+    FadingOut(Screen),
+    /// Revealing the new screen
+    FadingIn,
+}
+
+/// The full-screen overlay covering screen changes
+#[derive(Component)]
+struct FadeOverlay;
+
+// ===== SCREEN TRANSITION IMPLEMENTATION =====
+
+impl ScreenTransition {
+    /// Whether a screen change is in progress
+    pub fn is_active(&self) -> bool {
+        self.phase != FadePhase::Idle
+    }
+
+    /// How much the overlay covers the screen, between 0 and 1
+    fn coverage(&self, fade: &ScreenFade) -> f32 {
+        let t = if fade.duration > 0.0 {
+            (self.elapsed / fade.duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        match self.phase {
+            FadePhase::Idle => 0.0,
+            FadePhase::FadingOut(_) => fade.easing.sample_clamped(t),
+            FadePhase::FadingIn => 1.0 - fade.easing.sample_clamped(t),
+        }
+    }
+
+    /// Overlay placement for a wipe, growing from the left and leaving to the right
+    fn wipe_layout(&self, fade: &ScreenFade) -> UiLayout {
+        let width = 100.0 * self.coverage(fade);
+        let x = match self.phase {
+            FadePhase::FadingIn => 100.0 - width,
+            _ => 0.0,
+        };
+        UiLayout::window().x(Rl(x)).size(Rl((width, 100.0))).pack()
+    }
+
+    /// Fades out on screen requests and switches screen once it's covered
+    fn update(
+        time: Res<Time<Real>>,
+        fade: Res<ScreenFade>,
+        mut transition: ResMut<ScreenTransition>,
+        state: Res<State<Screen>>,
+        mut next: ResMut<NextState<Screen>>,
+        mut requests: EventReader<RequestScreen>,
+        mut fade_events: EventWriter<ScreenFadeStarted>,
+    ) {
+        // The latest request of the frame wins, repeating the one underway changes nothing
+        let request = requests.read().last().map(|request| request.0.clone());
+        if let Some(target) =
+            request.filter(|target| transition.phase != FadePhase::FadingOut(target.clone()))
+        {
+            if fade.duration <= 0.0 || cuts_between(state.get(), &target) {
+                next.set(target);
+                // Drop a fade out still heading somewhere else
+                if matches!(transition.phase, FadePhase::FadingOut(_)) {
+                    transition.elapsed = (fade.duration - transition.elapsed).max(0.0);
+                    transition.phase = FadePhase::FadingIn;
+                }
+            } else {
+                transition.elapsed = match transition.phase {
+                    FadePhase::Idle => 0.0,
+                    // Already fading out, keep going towards the latest request
+                    FadePhase::FadingOut(_) => transition.elapsed,
+                    // Turn around from wherever the fade in got to
+                    FadePhase::FadingIn => (fade.duration - transition.elapsed).max(0.0),
+                };
+                transition.phase = FadePhase::FadingOut(target.clone());
+                fade_events.write(ScreenFadeStarted { to: target });
+            }
+        }
+
+        if transition.phase == FadePhase::Idle {
+            return;
+        }
+
+        transition.elapsed += time.delta_secs();
+        if transition.elapsed < fade.duration {
+            return;
+        }
+
+        transition.elapsed = 0.0;
+        transition.phase = match std::mem::take(&mut transition.phase) {
+            FadePhase::FadingOut(target) => {
+                // Applied by the next state transition while the screen is covered
+                next.set(target);
+                FadePhase::FadingIn
+            }
+            _ => FadePhase::Idle,
+        };
+    }
+}
+
+/// Screens that sit on top of the running game switch instantly
+fn cuts_between(from: &Screen, to: &Screen) -> bool {
+    matches!(
+        (from, to),
+        (Screen::GamePlay, Screen::Pause) | (Screen::Pause, Screen::GamePlay)
+    )
+}
+
+// ===== FADE OVERLAY IMPLEMENTATION =====
+
+impl FadeOverlay {
+    fn spawn(mut commands: Commands, fade: Res<ScreenFade>) {
+        commands
+            .spawn((
+                Name::new("Screen Fade"),
+                UiLayoutRoot::new_2d(),
+                // Make the UI synchronized with camera viewport size
+                UiFetchFromCamera::<0>,
+            ))
+            .with_children(|ui| {
+                ui.spawn((
+                    UiLayout::window().full().pack(),
+                    // Stay above every screen
+                    UiDepth::Set(100.0),
+                    Sprite::default(),
+                    UiColor::from(fade.color.with_alpha(0.0)),
+                    Visibility::Hidden,
+                    FadeOverlay,
+                ));
+            });
+    }
+
+    /// Shows the overlay while a fade is running. It also blocks clicks meanwhile.
+    fn update(
+        fade: Res<ScreenFade>,
+        transition: Res<ScreenTransition>,
+        mut overlays: Query<(&mut UiColor, &mut UiLayout, &mut Visibility), With<FadeOverlay>>,
+    ) {
+        let (alpha, layout) = match fade.style {
+            FadeStyle::Fade => (transition.coverage(&fade), UiLayout::window().full().pack()),
+            FadeStyle::Wipe => (1.0, transition.wipe_layout(&fade)),
+        };
+        for (mut color, mut overlay_layout, mut visibility) in &mut overlays {
+            *color = UiColor::from(fade.color.with_alpha(fade.color.alpha() * alpha));
+            *overlay_layout = layout.clone();
+            *visibility = if transition.is_active() {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use avian3d::prelude::*;
+use bevy::{gltf::Gltf, platform::collections::{HashMap, HashSet}, prelude::*};
+use std::time::Duration;
+
+use crate::systems::character_controller::{Grounded, Stance};
+use crate::{AttackStarted, Footstep, HitFrame};
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<LocomotionClips>()
+        .add_event::<AttackStarted>()
+        .add_event::<Footstep>()
+        .add_event::<HitFrame>()
+        .add_observer(forward_footstep)
+        .add_observer(forward_hit_frame)
+        .add_systems(
+            Update,
+            (
+                build_locomotion_graph,
+                attach_locomotion_graph,
+                update_locomotion_state,
+            )
+                .chain(),
+        );
+}
+
+/// The locomotion states a character can animate in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LocomotionState {
+    #[default]
+    Idle,
+    Walk,
+    Run,
+    Jump,
+    Fall,
+    Land,
+    Crouch,
+    Attack,
+}
+
+impl LocomotionState {
+    pub const ALL: [LocomotionState; 8] = [
+        LocomotionState::Idle,
+        LocomotionState::Walk,
+        LocomotionState::Run,
+        LocomotionState::Jump,
+        LocomotionState::Fall,
+        LocomotionState::Land,
+        LocomotionState::Crouch,
+        LocomotionState::Attack,
+    ];
+
+    /// Whether the clip loops while the state is active
+    fn repeats(self) -> bool {
+        !matches!(self, Self::Jump | Self::Land | Self::Attack)
+    }
+}
+
+/// Where clip events fire and how states blend, shared by every model
+#[derive(Resource)]
+pub struct LocomotionClips {
+    /// Points in the walk and run cycles where a foot lands, as a fraction of the clip
+    pub footsteps: Vec<f32>,
+    /// Point in the attack clip where the hit connects, as a fraction of the clip
+    pub hit_frame: f32,
+    /// Crossfade time between states
+    pub blend: Duration,
+    /// Horizontal speed above which the character walks
+    pub walk_speed: f32,
+    /// Horizontal speed above which the character runs
+    pub run_speed: f32,
+    /// How long the landing and attack clips hold their state
+    pub land_duration: f32,
+    pub attack_duration: f32,
+}
+
+impl Default for LocomotionClips {
+    fn default() -> Self {
+        Self {
+            footsteps: vec![0.0, 0.5],
+            hit_frame: 0.4,
+            blend: Duration::from_millis(200),
+            walk_speed: 0.5,
+            run_speed: 12.0,
+            land_duration: 0.2,
+            attack_duration: 0.6,
+        }
+    }
+}
+
+/// Requests a locomotion animation graph built from the clips of a glTF file
+#[derive(Component)]
+pub struct LocomotionAnimations {
+    pub gltf: Handle<Gltf>,
+    /// Names of the glTF animations used for each state
+    pub clips: HashMap<LocomotionState, String>,
+}
+
+impl LocomotionAnimations {
+    /// Uses the clips named after each state, like "Idle" and "Walk"
+    pub fn new(gltf: Handle<Gltf>) -> Self {
+        Self {
+            gltf,
+            clips: LocomotionState::ALL
+                .into_iter()
+                .map(|state| (state, format!("{state:?}")))
+                .collect(),
+        }
+    }
+
+    /// Uses the clip called `name` for `state`, for models with their own naming
+    pub fn with_clip(mut self, state: LocomotionState, name: impl Into<String>) -> Self {
+        self.clips.insert(state, name.into());
+        self
+    }
+}
+
+/// The animation graph built for a character and the state it is animating
+#[derive(Component)]
+pub struct LocomotionAnimator {
+    graph: Handle<AnimationGraph>,
+    nodes: HashMap<LocomotionState, AnimationNodeIndex>,
+    /// The entity holding the `AnimationPlayer` inside the character's scene
+    rig: Option<Entity>,
+    pub state: LocomotionState,
+    was_grounded: bool,
+    land_remaining: f32,
+    attack_remaining: f32,
+}
+
+/// Points from the `AnimationPlayer` entity back to the animated character
+#[derive(Component)]
+struct LocomotionRig(Entity);
+
+/// Clip event for a foot touching the ground
+#[derive(Event, Reflect, Clone)]
+struct FootstepCue;
+
+/// Clip event for the frame an attack connects
+#[derive(Event, Reflect, Clone)]
+struct HitFrameCue;
+
+/// Builds the animation graph once the glTF file and its clips are loaded.
+fn build_locomotion_graph(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    clips: Res<LocomotionClips>,
+    gltfs: Res<Assets<Gltf>>,
+    mut animation_clips: ResMut<Assets<AnimationClip>>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+    mut annotated: Local<HashSet<AssetId<AnimationClip>>>,
+    query: Query<(Entity, &LocomotionAnimations), Without<LocomotionAnimator>>,
+) {
+    for (entity, animations) in &query {
+        if !asset_server.is_loaded_with_dependencies(&animations.gltf) {
+            continue;
+        }
+        let Some(gltf) = gltfs.get(&animations.gltf) else {
+            continue;
+        };
+
+        let mut graph = AnimationGraph::new();
+        let mut nodes = HashMap::new();
+        if gltf.named_animations.is_empty() {
+            let path = animations.gltf.path().map_or_else(String::new, ToString::to_string);
+            warn!("\"{}\" has no animations, the character won't animate", path);
+        }
+        for (state, name) in &animations.clips {
+            let Some(handle) = gltf.named_animations.get(name.as_str()) else {
+                // A model without any animations was already reported above
+                if !gltf.named_animations.is_empty() {
+                    warn!("Missing {:?} animation clip \"{}\"", state, name);
+                }
+                continue;
+            };
+
+            // Clips are shared between characters, so only add the events once
+            if annotated.insert(handle.id()) {
+                if let Some(clip) = animation_clips.get_mut(handle) {
+                    let duration = clip.duration();
+                    match state {
+                        LocomotionState::Walk | LocomotionState::Run => {
+                            for footstep in &clips.footsteps {
+                                clip.add_event(duration * footstep, FootstepCue);
+                            }
+                        }
+                        LocomotionState::Attack => {
+                            clip.add_event(duration * clips.hit_frame, HitFrameCue);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            nodes.insert(*state, graph.add_clip(handle.clone(), 1.0, graph.root));
+        }
+
+        commands.entity(entity).insert(LocomotionAnimator {
+            graph: graphs.add(graph),
+            nodes,
+            rig: None,
+            state: LocomotionState::Idle,
+            was_grounded: true,
+            land_remaining: 0.0,
+            attack_remaining: 0.0,
+        });
+    }
+}
+
+/// Hands the graph to the `AnimationPlayer` spawned somewhere in the character's scene.
+fn attach_locomotion_graph(
+    mut commands: Commands,
+    mut players: Query<(Entity, &mut AnimationPlayer), Without<LocomotionRig>>,
+    parents: Query<&ChildOf>,
+    mut animators: Query<&mut LocomotionAnimator>,
+) {
+    for (player, mut animation_player) in &mut players {
+        let Some((character, mut animator)) = parents
+            .iter_ancestors(player)
+            .find_map(|ancestor| {
+                animators
+                    .get_mut(ancestor)
+                    .ok()
+                    .map(|animator| (ancestor, animator))
+            })
+        else {
+            continue;
+        };
+
+        // Start on the state the character is already in, later changes blend from it
+        let mut transitions = AnimationTransitions::new();
+        if let Some(node) = animator.nodes.get(&animator.state) {
+            let animation = transitions.play(&mut animation_player, *node, Duration::ZERO);
+            if animator.state.repeats() {
+                animation.repeat();
+            }
+        }
+
+        animator.rig = Some(player);
+        commands.entity(player).insert((
+            AnimationGraphHandle(animator.graph.clone()),
+            transitions,
+            LocomotionRig(character),
+        ));
+    }
+}
+
+/// Picks the locomotion state from the controller and blends to its clip.
+fn update_locomotion_state(
+    time: Res<Time>,
+    clips: Res<LocomotionClips>,
+    mut attacks: EventReader<AttackStarted>,
+    mut characters: Query<(
+        Entity,
+        &mut LocomotionAnimator,
+        &LinearVelocity,
+        &Stance,
+        Has<Grounded>,
+    )>,
+    mut rigs: Query<(&mut AnimationPlayer, &mut AnimationTransitions)>,
+) {
+    let attackers: Vec<Entity> = attacks.read().map(|attack| attack.entity).collect();
+
+    for (entity, mut animator, velocity, stance, is_grounded) in &mut characters {
+        let delta = time.delta_secs();
+        animator.land_remaining = (animator.land_remaining - delta).max(0.0);
+        animator.attack_remaining = (animator.attack_remaining - delta).max(0.0);
+
+        if is_grounded && !animator.was_grounded {
+            animator.land_remaining = clips.land_duration;
+        }
+        animator.was_grounded = is_grounded;
+
+        let attacked = attackers.contains(&entity);
+        if attacked {
+            animator.attack_remaining = clips.attack_duration;
+        }
+
+        let speed = velocity.xz().length();
+        let state = if animator.attack_remaining > 0.0 {
+            LocomotionState::Attack
+        } else if !is_grounded {
+            if velocity.y > 0.0 {
+                LocomotionState::Jump
+            } else {
+                LocomotionState::Fall
+            }
+        } else if animator.land_remaining > 0.0 {
+            LocomotionState::Land
+        } else if *stance == Stance::Crouch {
+            LocomotionState::Crouch
+        } else if speed > clips.run_speed || (*stance == Stance::Sprint && speed > clips.walk_speed) {
+            LocomotionState::Run
+        } else if speed > clips.walk_speed {
+            LocomotionState::Walk
+        } else {
+            LocomotionState::Idle
+        };
+
+        // A new attack restarts the swing even while the last one is still playing
+        let restart = attacked && state == LocomotionState::Attack;
+        if state == animator.state && !restart {
+            continue;
+        }
+        animator.state = state;
+
+        let (Some(rig), Some(node)) = (animator.rig, animator.nodes.get(&state)) else {
+            continue;
+        };
+        let Ok((mut player, mut transitions)) = rigs.get_mut(rig) else {
+            continue;
+        };
+
+        let animation = transitions.play(&mut player, *node, clips.blend);
+        if state.repeats() {
+            animation.repeat();
+        }
+    }
+}
+
+fn forward_footstep(
+    trigger: Trigger<FootstepCue>,
+    rigs: Query<&LocomotionRig>,
+    mut footsteps: EventWriter<Footstep>,
+) {
+    if let Ok(rig) = rigs.get(trigger.target()) {
+        footsteps.write(Footstep { entity: rig.0 });
+    }
+}
+
+fn forward_hit_frame(
+    trigger: Trigger<HitFrameCue>,
+    rigs: Query<&LocomotionRig>,
+    mut hit_frames: EventWriter<HitFrame>,
+) {
+    if let Ok(rig) = rigs.get(trigger.target()) {
+        hit_frames.write(HitFrame { entity: rig.0 });
+    }
+}
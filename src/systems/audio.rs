@@ -1,13 +1,16 @@
 use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
-use std::time::Duration;
 
 use crate::screens::Screen;
+use crate::screens::transition::{ScreenFade, ScreenFadeStarted};
 
 pub fn plugin(app: &mut App) {
     app.init_resource::<AudioResources>()
         .add_systems(Startup, setup_audio)
-        .add_systems(Update, (handle_screen_transitions, instance_control));
+        .add_systems(
+            Update,
+            (fade_out_music, handle_screen_transitions, instance_control),
+        );
 }
 
 /// Control instance playback - currently this allows pausing/resuming with mouse click
@@ -38,9 +41,51 @@ fn instance_control(
 #[derive(Resource, Default)]
 struct AudioResources {
     current_music: Option<Handle<AudioInstance>>,
+    /// The track `current_music` is playing
+    current_track: Option<Handle<AudioSource>>,
     main_menu_track: Option<Handle<AudioSource>>,
 }
 
+impl AudioResources {
+    /// The music that plays on a screen
+    fn track_for(&self, screen: &Screen) -> Option<Handle<AudioSource>> {
+        match screen {
+            Screen::MainMenu | Screen::NewGame => self.main_menu_track.clone(),
+            // No music for other screens like gameplay
+            _ => None,
+        }
+    }
+
+    /// Fades out the playing music over `tween`
+    fn stop_music(&mut self, audio_instances: &mut Assets<AudioInstance>, tween: AudioTween) {
+        if let Some(handle) = self.current_music.take() {
+            if let Some(instance) = audio_instances.get_mut(&handle) {
+                instance.stop(tween);
+            }
+        }
+        self.current_track = None;
+    }
+}
+
+/// Music fades over the same time as each half of the screen fade
+fn fade_tween(fade: &ScreenFade) -> AudioTween {
+    AudioTween::new(fade.half(), AudioEasing::OutPowf(2.0))
+}
+
+/// Fades the music out together with the screen when the next screen plays another track
+fn fade_out_music(
+    mut fade_event_reader: EventReader<ScreenFadeStarted>,
+    fade: Res<ScreenFade>,
+    mut audio_resources: ResMut<AudioResources>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    for event in fade_event_reader.read() {
+        if audio_resources.track_for(&event.to) != audio_resources.current_track {
+            audio_resources.stop_music(&mut audio_instances, fade_tween(&fade));
+        }
+    }
+}
+
 /// Setup audio resources and load assets
 fn setup_audio(
     // mut commands: Commands,
@@ -54,6 +99,7 @@ fn setup_audio(
 /// System to handle screen transitions and play appropriate music
 fn handle_screen_transitions(
     audio: Res<Audio>,
+    fade: Res<ScreenFade>,
     mut audio_resources: ResMut<AudioResources>,
     mut audio_instances: ResMut<Assets<AudioInstance>>,
     current_state: Res<State<Screen>>,
@@ -67,47 +113,23 @@ fn handle_screen_transitions(
     // Update previous state for next frame
     *prev_state = current_state.get().clone();
 
-    // Stop any currently playing music with a fade out
-    if let Some(handle) = &audio_resources.current_music {
-        if let Some(instance) = audio_instances.get_mut(handle) {
-            instance.stop(AudioTween::new(
-                Duration::from_secs(1),
-                AudioEasing::OutPowf(2.0),
-            ));
-        }
-        audio_resources.current_music = None;
+    // Keep the music going between screens sharing a track
+    let track = audio_resources.track_for(current_state.get());
+    if track == audio_resources.current_track {
+        return;
     }
 
-    // Play appropriate music based on the current screen
-    match current_state.get() {
-        Screen::MainMenu => {
-            if let Some(track) = &audio_resources.main_menu_track {
-                let handle = audio
-                    .play(track.clone())
-                    .looped()
-                    .fade_in(AudioTween::new(
-                        Duration::new(2, 0),
-                        AudioEasing::OutPowf(2.0),
-                    ))
-                    .handle();
-                audio_resources.current_music = Some(handle);
-            }
-        }
-        Screen::NewGame => {
-            if let Some(track) = &audio_resources.main_menu_track {
-                let handle = audio
-                    .play(track.clone())
-                    .looped()
-                    .fade_in(AudioTween::new(
-                        Duration::new(2, 0),
-                        AudioEasing::OutPowf(2.0),
-                    ))
-                    .handle();
-                audio_resources.current_music = Some(handle);
-            }
-        }
-        _ => {
-            // No music for other screens like gameplay
-        }
+    // Usually already faded out with the screen, unless the change was a cut
+    audio_resources.stop_music(&mut audio_instances, fade_tween(&fade));
+
+    // Fade the new music in while the screen fades back in
+    if let Some(track) = track {
+        let handle = audio
+            .play(track.clone())
+            .looped()
+            .fade_in(fade_tween(&fade))
+            .handle();
+        audio_resources.current_music = Some(handle);
+        audio_resources.current_track = Some(track);
     }
 }
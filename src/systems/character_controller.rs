@@ -1,6 +1,12 @@
 use avian3d::{math::*, prelude::*};
-use bevy::{ecs::query::Has, prelude::*};
-use crate::{rendering::cameras::player_camera::FlyCam, Player};
+use bevy::{ecs::query::Has, platform::collections::HashMap, prelude::*};
+use crate::{
+    rendering::cameras::{
+        camera_mode::CameraMode, cinematic::CinematicPlayback, player_camera::FlyCam,
+    },
+    ControlScheme, Dashed, GameSettings, Invulnerable, Player,
+    Stamina, StaminaChanged,
+};
 
 pub struct CharacterControllerPlugin;
 
@@ -8,41 +14,141 @@ impl Plugin for CharacterControllerPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<LastInputDirection>()
             .add_event::<MovementAction>()
+            .add_event::<StaminaChanged>()
+            .add_event::<Dashed>()
+            // Run alongside the physics schedule so movement is frame rate independent
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
-                    keyboard_input,
-                    gamepad_input,
+                    // The free debug camera and cinematics take over the movement input
+                    (keyboard_input, gamepad_input).run_if(
+                        not(resource_equals(CameraMode::FreeDebug))
+                            .and(not(any_with_component::<CinematicPlayback>)),
+                    ),
                     update_grounded,
+                    carry_riders,
+                    update_stance,
+                    tick_dash,
                     movement,
+                    climb_steps,
+                    slide_on_steep_slopes,
+                    apply_jump,
+                    drain_sprint_stamina,
+                    regenerate_stamina,
+                    report_stamina,
                     apply_movement_damping,
-                    camera_follow_player_system,
+                    kinematic_collide_and_slide,
                 )
-                    .chain(),
+                    .chain()
+                    .in_set(CharacterControllerSystems),
+            )
+            .add_systems(
+                Update,
+                sync_control_scheme.run_if(resource_changed::<GameSettings>),
             );
     }
 }
 
+/// The fixed step systems moving character controllers. Anything moving the
+/// ground they stand on, like platforms, should run before it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CharacterControllerSystems;
+
 /// An event sent for a movement input action.
 #[derive(Event)]
 pub enum MovementAction {
     Move(Vector2),
     Jump,
+    Dodge,
 }
 
 /// A marker component indicating that an entity is using a character controller.
 #[derive(Component)]
 pub struct CharacterController;
 
+/// How fast a camera relative character turns towards its movement, as the fraction
+/// of the remaining angle covered per second.
+const CAMERA_RELATIVE_TURN_SPEED: f32 = 10.0;
+
+/// A marker component for character controllers driven by [`kinematic_collide_and_slide`]
+/// instead of the physics solver.
+#[derive(Component)]
+pub struct KinematicController;
+
 /// A marker component indicating that an entity is on the ground.
 #[derive(Component)]
 #[component(storage = "SparseSet")]
 pub struct Grounded;
+/// The normal of the ground the character is standing on, or [`Vector::Y`] while airborne.
+#[derive(Component, Debug)]
+pub struct GroundNormal(pub Vector);
+
+impl Default for GroundNormal {
+    fn default() -> Self {
+        Self(Vector::Y)
+    }
+}
+
+/// The body a grounded character controller is standing on.
+#[derive(Component, Default, Debug)]
+pub struct GroundEntity(pub Option<Entity>);
+
+/// The maximum angle a slope can have for a character controller
+/// to be able to stand on it.
+#[derive(Component)]
+pub struct MaxSlopeAngle(Scalar);
+
+/// The tallest obstacle a grounded character controller steps onto automatically.
+#[derive(Component)]
+pub struct MaxStepHeight(Scalar);
+
+/// How fast a character controller accelerates down slopes steeper than [`MaxSlopeAngle`].
+#[derive(Component)]
+pub struct SlopeSlideAcceleration(Scalar);
+
+/// Timing windows that make jumping more forgiving.
+#[derive(Component)]
+pub struct JumpWindows {
+    /// Seconds after leaving the ground during which a jump is still allowed.
+    pub coyote_time: f32,
+    /// Seconds a jump pressed in the air is remembered before landing.
+    pub jump_buffer: f32,
+    coyote_remaining: f32,
+    buffer_remaining: f32,
+    buffered: bool,
+    /// Seconds after a jump during which ground hits are ignored.
+    lockout_remaining: f32,
+}
+
+impl JumpWindows {
+    /// Seconds a jump keeps the character airborne, so the ground it just
+    /// left can't refill the coyote time and allow a second jump.
+    const LOCKOUT: f32 = 0.15;
+
+    pub const fn new(coyote_time: f32, jump_buffer: f32) -> Self {
+        Self {
+            coyote_time,
+            jump_buffer,
+            coyote_remaining: 0.0,
+            buffer_remaining: 0.0,
+            buffered: false,
+            lockout_remaining: 0.0,
+        }
+    }
+}
+
+impl Default for JumpWindows {
+    fn default() -> Self {
+        Self::new(0.15, 0.15)
+    }
+}
+
 /// The acceleration used for character movement.
 #[derive(Component)]
 pub struct MovementAcceleration(Scalar);
 
-/// The damping factor used for slowing down movement.
+/// The damping factor used for slowing down movement, as the fraction of
+/// horizontal velocity kept every 1/60th of a second.
 #[derive(Component)]
 pub struct MovementDampingFactor(Scalar);
 
@@ -50,16 +156,171 @@ pub struct MovementDampingFactor(Scalar);
 #[derive(Component)]
 pub struct JumpImpulse(Scalar);
 
+/// The stamina spent by each movement action.
+#[derive(Component)]
+pub struct StaminaCosts {
+    /// Stamina drained per second while sprinting.
+    pub sprint_per_second: f32,
+    pub jump: f32,
+    pub dodge: f32,
+}
+
+impl Default for StaminaCosts {
+    fn default() -> Self {
+        Self {
+            sprint_per_second: 15.0,
+            jump: 10.0,
+            dodge: 25.0,
+        }
+    }
+}
+
+/// Tuning for the dash ability.
+#[derive(Component)]
+pub struct DashSettings {
+    /// Horizontal speed of the dash burst.
+    pub speed: Scalar,
+    /// Seconds before another dash can start.
+    pub cooldown: f32,
+    /// Seconds of invulnerability granted at the start of a dash.
+    pub invulnerability: f32,
+}
+
+impl Default for DashSettings {
+    fn default() -> Self {
+        Self {
+            speed: 40.0,
+            cooldown: 0.8,
+            invulnerability: 0.3,
+        }
+    }
+}
+
+/// Seconds left before the next dash is available.
+#[derive(Component, Default)]
+pub struct DashCooldown(pub f32);
+
+/// The current movement stance of a character controller.
+#[derive(Component, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stance {
+    #[default]
+    Walk,
+    Sprint,
+    Crouch,
+}
+
+/// The stance requested by input, resolved into a [`Stance`] by [`update_stance`].
+#[derive(Component, Default, Debug)]
+pub struct StanceRequest {
+    pub sprint: bool,
+    pub crouch: bool,
+}
+
+/// Tuning values and collider shapes used by each [`Stance`].
+#[derive(Component)]
+pub struct StanceSettings {
+    /// Multiplier applied to [`MovementAcceleration`] while sprinting.
+    pub sprint_multiplier: Scalar,
+    /// Multiplier applied to [`MovementAcceleration`] while crouching.
+    pub crouch_multiplier: Scalar,
+    /// How far the follow camera is lowered while crouching.
+    pub crouch_camera_drop: f32,
+    standing_collider: Collider,
+    crouching_collider: Collider,
+    /// Half the height difference between the standing and crouching colliders.
+    height_offset: Scalar,
+}
+
+impl StanceSettings {
+    pub fn new(
+        collider: &Collider,
+        sprint_multiplier: Scalar,
+        crouch_multiplier: Scalar,
+        crouch_height_scale: Scalar,
+    ) -> Self {
+        let crouching_collider = match collider.shape().as_capsule() {
+            Some(capsule) => Collider::capsule(
+                capsule.radius,
+                capsule.half_height() * 2.0 * crouch_height_scale,
+            ),
+            None => {
+                let mut crouching = collider.clone();
+                crouching.set_scale(Vector::new(1.0, crouch_height_scale, 1.0), 10);
+                crouching
+            }
+        };
+
+        let half_height =
+            |collider: &Collider| collider.aabb(Vector::ZERO, Quaternion::IDENTITY).size().y / 2.0;
+
+        Self {
+            sprint_multiplier,
+            crouch_multiplier,
+            crouch_camera_drop: 1.5,
+            height_offset: half_height(collider) - half_height(&crouching_collider),
+            standing_collider: collider.clone(),
+            crouching_collider,
+        }
+    }
+
+    /// The acceleration multiplier for the given stance.
+    pub fn multiplier(&self, stance: Stance) -> Scalar {
+        match stance {
+            Stance::Walk => 1.0,
+            Stance::Sprint => self.sprint_multiplier,
+            Stance::Crouch => self.crouch_multiplier,
+        }
+    }
+}
+
 /// A bundle that contains the components needed for a basic
 /// kinematic character controller.
 #[derive(Bundle)]
 pub struct CharacterControllerBundle {
     character_controller: CharacterController,
+    control_scheme: ControlScheme,
     body: RigidBody,
     collider: Collider,
     ground_caster: ShapeCaster,
+    ground_normal: GroundNormal,
+    ground_entity: GroundEntity,
+    max_slope_angle: MaxSlopeAngle,
+    max_step_height: MaxStepHeight,
+    slope_slide: SlopeSlideAcceleration,
+    jump_windows: JumpWindows,
     locked_axes: LockedAxes,
     movement: MovementBundle,
+    stance: Stance,
+    stance_request: StanceRequest,
+    stance_settings: StanceSettings,
+    stamina_costs: StaminaCosts,
+    dash: DashSettings,
+    dash_cooldown: DashCooldown,
+}
+
+/// A bundle for a kinematic character controller that collides and slides
+/// along surfaces with shape casts. It shares every movement component with
+/// [`CharacterControllerBundle`], so the two can be swapped freely.
+#[derive(Bundle)]
+pub struct KinematicCharacterControllerBundle {
+    controller: CharacterControllerBundle,
+    kinematic: KinematicController,
+}
+
+impl KinematicCharacterControllerBundle {
+    pub fn new(collider: Collider) -> Self {
+        CharacterControllerBundle::new(collider).kinematic()
+    }
+}
+
+impl From<CharacterControllerBundle> for KinematicCharacterControllerBundle {
+    fn from(mut controller: CharacterControllerBundle) -> Self {
+        controller.body = RigidBody::Kinematic;
+        Self {
+            controller,
+            kinematic: KinematicController,
+        }
+    }
 }
 
 /// A bundle that contains components for character movement.
@@ -90,14 +351,21 @@ impl Default for MovementBundle {
     }
 }
 
+/// Creates the ground caster shape as a slightly smaller version of the collider.
+fn caster_shape(collider: &Collider) -> Collider {
+    let mut caster_shape = collider.clone();
+    caster_shape.set_scale(Vector::ONE * 0.99, 10);
+    caster_shape
+}
+
 impl CharacterControllerBundle {
     pub fn new(collider: Collider) -> Self {
-        // Create shape caster as a slightly smaller version of collider
-        let mut caster_shape = collider.clone();
-        caster_shape.set_scale(Vector::ONE * 0.99, 10);
+        let caster_shape = caster_shape(&collider);
+        let stance_settings = StanceSettings::new(&collider, 1.6, 0.5, 0.25);
 
         Self {
             character_controller: CharacterController,
+            control_scheme: ControlScheme::default(),
             body: RigidBody::Dynamic,
             collider,
             ground_caster: ShapeCaster::new(
@@ -107,11 +375,28 @@ impl CharacterControllerBundle {
                 Dir3::NEG_Y,
             )
             .with_max_distance(0.2),
+            ground_normal: GroundNormal::default(),
+            ground_entity: GroundEntity::default(),
+            max_slope_angle: MaxSlopeAngle(PI * 0.25),
+            max_step_height: MaxStepHeight(0.3),
+            slope_slide: SlopeSlideAcceleration(30.0),
+            jump_windows: JumpWindows::default(),
             locked_axes: LockedAxes::ROTATION_LOCKED,
             movement: MovementBundle::default(),
+            stance: Stance::default(),
+            stance_request: StanceRequest::default(),
+            stance_settings,
+            stamina_costs: StaminaCosts::default(),
+            dash: DashSettings::default(),
+            dash_cooldown: DashCooldown::default(),
         }
     }
 
+    /// Turns this into a [`KinematicCharacterControllerBundle`] with the same configuration.
+    pub fn kinematic(self) -> KinematicCharacterControllerBundle {
+        self.into()
+    }
+
     pub fn with_movement(
         mut self,
         acceleration: Scalar,
@@ -121,6 +406,64 @@ impl CharacterControllerBundle {
         self.movement = MovementBundle::new(acceleration, damping, jump_impulse);
         self
     }
+
+    pub fn with_stance(
+        mut self,
+        sprint_multiplier: Scalar,
+        crouch_multiplier: Scalar,
+        crouch_height_scale: Scalar,
+    ) -> Self {
+        self.stance_settings = StanceSettings::new(
+            &self.collider,
+            sprint_multiplier,
+            crouch_multiplier,
+            crouch_height_scale,
+        );
+        self
+    }
+
+    pub fn with_control_scheme(mut self, control_scheme: ControlScheme) -> Self {
+        self.control_scheme = control_scheme;
+        self
+    }
+
+    pub fn with_max_slope_angle(mut self, max_slope_angle: Scalar) -> Self {
+        self.max_slope_angle = MaxSlopeAngle(max_slope_angle);
+        self
+    }
+
+    pub fn with_step_height(mut self, max_step_height: Scalar) -> Self {
+        self.max_step_height = MaxStepHeight(max_step_height);
+        self
+    }
+
+    pub fn with_slope_slide(mut self, acceleration: Scalar) -> Self {
+        self.slope_slide = SlopeSlideAcceleration(acceleration);
+        self
+    }
+
+    pub fn with_jump_windows(mut self, coyote_time: f32, jump_buffer: f32) -> Self {
+        self.jump_windows = JumpWindows::new(coyote_time, jump_buffer);
+        self
+    }
+
+    pub fn with_dash(mut self, speed: Scalar, cooldown: f32, invulnerability: f32) -> Self {
+        self.dash = DashSettings {
+            speed,
+            cooldown,
+            invulnerability,
+        };
+        self
+    }
+
+    pub fn with_stamina_costs(mut self, sprint_per_second: f32, jump: f32, dodge: f32) -> Self {
+        self.stamina_costs = StaminaCosts {
+            sprint_per_second,
+            jump,
+            dodge,
+        };
+        self
+    }
 }
 
 /// Add a resource to store the last movement input for camera rotation
@@ -128,6 +471,9 @@ impl CharacterControllerBundle {
 pub struct LastInputDirection(pub Vec2);
 
 /// Sends [`MovementAction`] events based on keyboard input.
+///
+/// Only held keys are sampled here, one-shot actions like jumping come from
+/// the input actions so they are neither missed nor repeated by fixed steps.
 fn keyboard_input(
     mut movement_event_writer: EventWriter<MovementAction>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
@@ -146,10 +492,6 @@ fn keyboard_input(
         movement_event_writer.write(MovementAction::Move(direction));
         last_input.0 = direction.as_dvec2().as_vec2();
     }
-
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        movement_event_writer.write(MovementAction::Jump);
-    }
 }
 
 /// Sends [`MovementAction`] events based on gamepad input.
@@ -166,125 +508,671 @@ fn gamepad_input(
                 Vector2::new(x as Scalar, y as Scalar).clamp_length_max(1.0),
             ));
         }
-
-        if gamepad.just_pressed(GamepadButton::South) {
-            movement_event_writer.write(MovementAction::Jump);
-        }
     }
 }
 
-/// Updates the [`Grounded`] status for character controllers.
+/// Updates the [`Grounded`] status for character controllers from the hits
+/// of their ground caster, ignoring surfaces steeper than [`MaxSlopeAngle`].
 fn update_grounded(
     mut commands: Commands,
-    mut query: Query<(Entity, &LinearVelocity), With<CharacterController>>,
+    time: Res<Time>,
+    mut query: Query<
+        (
+            Entity,
+            &ShapeHits,
+            &Rotation,
+            &MaxSlopeAngle,
+            &mut GroundNormal,
+            &mut GroundEntity,
+            &mut JumpWindows,
+        ),
+        With<CharacterController>,
+    >,
+    collider_bodies: Query<&ColliderOf>,
 ) {
-    for (entity, velocity) in &mut query {
-        // Consider grounded only when velocity is very close to zero
-        let is_grounded = velocity.y.abs() < 0.1; // Check if we're actually on the ground
+    for (
+        entity,
+        hits,
+        rotation,
+        max_slope_angle,
+        mut ground_normal,
+        mut ground_entity,
+        mut jump_windows,
+    ) in &mut query
+    {
+        jump_windows.lockout_remaining = (jump_windows.lockout_remaining - time.delta_secs()).max(0.0);
+
+        // The hit normal points into the caster, so flip it to get the surface normal
+        let ground = hits
+            .iter()
+            .map(|hit| (hit.entity, rotation * -hit.normal2))
+            .find(|(_, normal)| normal.angle_between(Vector::Y).abs() <= max_slope_angle.0)
+            // Right after a jump the caster still touches the ground being left
+            .filter(|_| jump_windows.lockout_remaining <= 0.0);
 
-        if is_grounded {
+        if let Some((hit_entity, normal)) = ground {
             commands.entity(entity).insert(Grounded);
+            ground_normal.0 = normal;
+            // Colliders can be children of the body that moves them
+            ground_entity.0 = Some(
+                collider_bodies
+                    .get(hit_entity)
+                    .map_or(hit_entity, |collider_of| collider_of.body),
+            );
+            jump_windows.coyote_remaining = jump_windows.coyote_time;
         } else {
             commands.entity(entity).remove::<Grounded>();
+            ground_normal.0 = Vector::Y;
+            ground_entity.0 = None;
+            jump_windows.coyote_remaining = (jump_windows.coyote_remaining - time.delta_secs()).max(0.0);
+        }
+    }
+}
+
+/// Moves character controllers along with the kinematic body they stand on,
+/// such as a [`MovingPlatform`](super::platforms::MovingPlatform), so they
+/// ride it without sliding off.
+fn carry_riders(
+    time: Res<Time>,
+    mut riders: Query<(&GroundEntity, &mut Transform), With<CharacterController>>,
+    bodies: Query<
+        (&RigidBody, &LinearVelocity, &AngularVelocity, &Transform),
+        Without<CharacterController>,
+    >,
+) {
+    let delta_time = time.delta_secs_f64().adjust_precision();
+
+    for (ground_entity, mut transform) in &mut riders {
+        let Some(Ok((body, linear_velocity, angular_velocity, body_transform))) =
+            ground_entity.0.map(|ground| bodies.get(ground))
+        else {
+            continue;
+        };
+        if !body.is_kinematic() {
+            continue;
+        }
+
+        // Follow the body's rotation around its origin as well as its movement
+        let spin = Quat::from_rotation_y((angular_velocity.y * delta_time) as f32);
+        let offset = transform.translation - body_transform.translation;
+        let displacement =
+            (linear_velocity.0 * delta_time).f32() + (spin * offset - offset);
+
+        transform.translation += displacement;
+        transform.rotation = spin * transform.rotation;
+    }
+}
+
+/// Resolves the [`StanceRequest`] into a [`Stance`], swapping the collider and
+/// ground caster when crouching. Standing up is refused while something is overhead.
+fn update_stance(
+    spatial_query: SpatialQuery,
+    mut query: Query<
+        (
+            Entity,
+            &StanceRequest,
+            &StanceSettings,
+            Option<&Stamina>,
+            &mut Stance,
+            &mut Collider,
+            &mut ShapeCaster,
+            &mut Transform,
+        ),
+        With<CharacterController>,
+    >,
+) {
+    for (entity, request, settings, stamina, mut stance, mut collider, mut caster, mut transform) in
+        &mut query
+    {
+        let can_sprint = stamina.is_none_or(|stamina| !stamina.exhausted);
+
+        let wanted = if request.crouch {
+            Stance::Crouch
+        } else if request.sprint && can_sprint {
+            Stance::Sprint
+        } else {
+            Stance::Walk
+        };
+
+        if wanted == *stance {
+            continue;
+        }
+
+        // Colliders are scaled by the transform, so the offset has to be too
+        let offset = settings.height_offset * transform.scale.y.adjust_precision();
+
+        match (*stance, wanted) {
+            (_, Stance::Crouch) => {
+                *collider = settings.crouching_collider.clone();
+                caster.shape = caster_shape(&collider);
+                // Keep the feet planted while the collider shrinks
+                transform.translation.y -= offset as f32;
+            }
+            (Stance::Crouch, _) => {
+                // Sweep the crouching shape through the extra headroom standing needs.
+                // It is slightly thinner so walls being touched don't count.
+                let mut crouching = settings.crouching_collider.clone();
+                crouching.set_scale(transform.scale.adjust_precision() * 0.99, 10);
+                let config = ShapeCastConfig {
+                    // Ignore the ground the character is resting on
+                    ignore_origin_penetration: true,
+                    ..ShapeCastConfig::from_max_distance(offset * 2.0)
+                };
+
+                // Only surfaces facing down are overhead
+                let blocked = spatial_query
+                    .shape_hits(
+                        &crouching,
+                        transform.translation.adjust_precision(),
+                        transform.rotation.adjust_precision(),
+                        Dir3::Y,
+                        4,
+                        &config,
+                        &SpatialQueryFilter::from_excluded_entities([entity]),
+                    )
+                    .iter()
+                    .any(|hit| hit.normal1.y < 0.0);
+                if blocked {
+                    continue;
+                }
+
+                *collider = settings.standing_collider.clone();
+                caster.shape = caster_shape(&collider);
+                transform.translation.y += offset as f32;
+            }
+            _ => {}
+        }
+
+        *stance = wanted;
+    }
+}
+
+/// Counts down dash cooldowns and invulnerability windows.
+fn tick_dash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cooldowns: Query<&mut DashCooldown>,
+    mut invulnerable: Query<(Entity, &mut Invulnerable)>,
+) {
+    for mut cooldown in &mut cooldowns {
+        if cooldown.0 > 0.0 {
+            cooldown.0 = (cooldown.0 - time.delta_secs()).max(0.0);
+        }
+    }
+
+    for (entity, mut invulnerable) in &mut invulnerable {
+        invulnerable.remaining -= time.delta_secs();
+        if invulnerable.remaining <= 0.0 {
+            commands.entity(entity).remove::<Invulnerable>();
         }
     }
 }
 
 /// Responds to [`MovementAction`] events and moves character controllers accordingly.
 fn movement(
+    mut commands: Commands,
     time: Res<Time>,
     mut movement_event_reader: EventReader<MovementAction>,
+    mut dash_event_writer: EventWriter<Dashed>,
+    cameras: Query<&GlobalTransform, With<FlyCam>>,
     mut controllers: Query<(
+        Entity,
+        &ControlScheme,
         &MovementAcceleration,
-        &JumpImpulse,
+        &Stance,
+        &StanceSettings,
+        &mut JumpWindows,
+        &StaminaCosts,
+        Option<&mut Stamina>,
+        &DashSettings,
+        &mut DashCooldown,
+        &GroundNormal,
         &mut LinearVelocity,
         &mut Transform,
-        Has<Grounded>,
     )>,
 ) {
     let delta_time = time.delta_secs_f64().adjust_precision();
     let rotation_speed = 2.5;
 
-    for event in movement_event_reader.read() {
-        for (movement_acceleration, jump_impulse, mut linear_velocity, mut transform, is_grounded) in
-            &mut controllers
+    let camera = cameras.iter().next();
+    let events: Vec<&MovementAction> = movement_event_reader.read().collect();
+
+    // The latest movement input steers dashes started this frame
+    let move_input = events.iter().rev().find_map(|event| match event {
+        MovementAction::Move(direction) => Some(*direction),
+        _ => None,
+    });
+
+    for event in events {
+        for (
+            entity,
+            control_scheme,
+            movement_acceleration,
+            stance,
+            stance_settings,
+            mut jump_windows,
+            stamina_costs,
+            mut stamina,
+            dash,
+            mut dash_cooldown,
+            ground_normal,
+            mut linear_velocity,
+            mut transform,
+        ) in &mut controllers
         {
+            let exhaustion = stamina.as_ref().map_or(1.0, |stamina| stamina.speed_multiplier());
+            let acceleration =
+                movement_acceleration.0 * stance_settings.multiplier(*stance) * exhaustion.adjust_precision();
+
+            // Spends stamina for an action, refusing it when it can't be afforded.
+            // Checked before borrowing mutably so a refusal doesn't mark it changed.
+            let mut afford = |cost: f32| match stamina.as_mut() {
+                Some(stamina) if stamina.can_afford(cost) => {
+                    stamina.drain(cost);
+                    true
+                }
+                Some(_) => false,
+                None => true,
+            };
+
             match event {
                 MovementAction::Move(direction) => {
-                    // Rotate player based on horizontal input
-                    if direction.x != 0.0 {
-                        let rotation_amount = direction.x * rotation_speed * delta_time as f32;
-                        transform.rotate_y(-rotation_amount);
-                    }
+                    let movement_direction = match control_scheme {
+                        ControlScheme::Tank => {
+                            // Rotate player based on horizontal input
+                            if direction.x != 0.0 {
+                                let rotation_amount = direction.x * rotation_speed * delta_time as f32;
+                                transform.rotate_y(-rotation_amount);
+                            }
+
+                            // Calculate movement direction relative to player's rotation (inverted)
+                            (transform.forward() * -direction.y as f32).as_dvec3().as_vec3()
+                        }
+                        ControlScheme::CameraRelative => {
+                            let movement_direction = camera_relative(*direction, camera);
 
-                    // Get player's forward and right vectors
-                    let forward = transform.forward();
-                    let _right = transform.right();
+                            // Turn smoothly to face the movement direction
+                            if movement_direction != Vec3::ZERO {
+                                let target = facing_rotation(movement_direction);
+                                transform.rotation = transform.rotation.slerp(
+                                    target,
+                                    (CAMERA_RELATIVE_TURN_SPEED * delta_time as f32).min(1.0),
+                                );
+                            }
+                            movement_direction
+                        }
+                    };
+
+                    // Follow the ground plane so slopes neither slow nor launch the character
+                    let movement_direction =
+                        project_on_plane(movement_direction.adjust_precision(), ground_normal.0)
+                            .normalize_or_zero()
+                            * movement_direction.length().adjust_precision();
 
-                    // Calculate movement direction relative to player's rotation (inverted)
-                    let movement_direction = (forward * -direction.y as f32).as_dvec3().as_vec3();
-                    
                     // Apply movement in the direction the player is facing
-                    linear_velocity.x += movement_direction.x * movement_acceleration.0 * delta_time;
-                    linear_velocity.z += movement_direction.z * movement_acceleration.0 * delta_time;
+                    linear_velocity.0 += movement_direction * acceleration * delta_time;
                 }
                 MovementAction::Jump => {
-                    // Only jump if grounded
-                    if is_grounded {
-                        linear_velocity.y = jump_impulse.0;
+                    // Buffer the jump, `apply_jump` performs it once allowed
+                    jump_windows.buffered = true;
+                    jump_windows.buffer_remaining = jump_windows.jump_buffer;
+                }
+                MovementAction::Dodge => {
+                    if dash_cooldown.0 > 0.0 || !afford(stamina_costs.dodge) {
+                        continue;
                     }
+
+                    // Dash along the movement input, or the facing direction without one
+                    let forward = transform.forward().as_vec3();
+                    let direction = match (control_scheme, move_input) {
+                        (ControlScheme::Tank, Some(input)) if input.y != 0.0 => {
+                            forward * -input.y.signum() as f32
+                        }
+                        (ControlScheme::CameraRelative, Some(input)) if input != Vector2::ZERO => {
+                            camera_relative(input, camera)
+                        }
+                        _ => -forward,
+                    };
+
+                    linear_velocity.x = direction.x.adjust_precision() * dash.speed;
+                    linear_velocity.z = direction.z.adjust_precision() * dash.speed;
+                    dash_cooldown.0 = dash.cooldown;
+
+                    commands.entity(entity).insert(Invulnerable {
+                        remaining: dash.invulnerability,
+                    });
+                    dash_event_writer.write(Dashed { entity, direction });
                 }
             }
         }
     }
 }
 
-/// Slows down movement in the XZ plane and prevents unwanted vertical movement
-fn apply_movement_damping(mut query: Query<(&MovementDampingFactor, &mut LinearVelocity)>) {
-    for (damping_factor, mut linear_velocity) in &mut query {
-        // Dampen horizontal movement
-        linear_velocity.x *= damping_factor.0;
-        linear_velocity.z *= damping_factor.0;
-        
-        // Apply gravity if not grounded
-        if linear_velocity.y.abs() >= 0.1 { // Apply gravity when not on ground
-            linear_velocity.y -= 9.8 * 0.016; // Apply gravity
+/// Maps movement input onto the XZ plane relative to the camera's yaw.
+fn camera_relative(input: Vector2, camera: Option<&GlobalTransform>) -> Vec3 {
+    let (forward, right) = camera.map_or((Vec3::NEG_Z, Vec3::X), |camera| {
+        (
+            camera.forward().with_y(0.0).normalize_or_zero(),
+            camera.right().with_y(0.0).normalize_or_zero(),
+        )
+    });
+    (right * input.x as f32 + forward * input.y as f32).clamp_length_max(1.0)
+}
+
+/// The rotation that turns the model's front (+Z) towards `direction`.
+pub(crate) fn facing_rotation(direction: Vec3) -> Quat {
+    Quat::from_rotation_y(direction.x.atan2(direction.z))
+}
+
+/// Applies the control scheme chosen in the settings to the player.
+fn sync_control_scheme(
+    settings: Res<GameSettings>,
+    mut query: Query<&mut ControlScheme, With<Player>>,
+) {
+    for mut control_scheme in &mut query {
+        control_scheme.set_if_neq(settings.control_scheme);
+    }
+}
+
+/// Removes the part of `vector` along the plane `normal`.
+fn project_on_plane(vector: Vector, normal: Vector) -> Vector {
+    vector - normal * vector.dot(normal)
+}
+
+/// Lifts grounded character controllers onto obstacles lower than [`MaxStepHeight`].
+fn climb_steps(
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    mut query: Query<
+        (
+            Entity,
+            &Collider,
+            &MaxStepHeight,
+            &MaxSlopeAngle,
+            &LinearVelocity,
+            &mut Transform,
+        ),
+        With<Grounded>,
+    >,
+) {
+    let delta_time = time.delta_secs_f64().adjust_precision();
+
+    for (entity, collider, max_step_height, max_slope_angle, linear_velocity, mut transform) in
+        &mut query
+    {
+        let horizontal = Vector::new(linear_velocity.x, 0.0, linear_velocity.z);
+        let Ok(direction) = Dir3::new(horizontal.f32()) else {
+            continue;
+        };
+
+        // Look slightly further than this step will move
+        let distance = horizontal.length() * delta_time + 0.05;
+        let position = transform.translation.adjust_precision();
+        let rotation = transform.rotation.adjust_precision();
+        let filter = SpatialQueryFilter::from_excluded_entities([entity]);
+        let config = ShapeCastConfig {
+            ignore_origin_penetration: true,
+            ..ShapeCastConfig::from_max_distance(distance)
+        };
+
+        // Only obstacles too steep to walk up need stepping over
+        let Some(hit) =
+            spatial_query.cast_shape(collider, position, rotation, direction, &config, &filter)
+        else {
+            continue;
+        };
+        if hit.normal1.angle_between(Vector::Y) <= max_slope_angle.0 {
+            continue;
+        }
+
+        // The way forward has to be clear at step height
+        let raised = position + Vector::Y * max_step_height.0;
+        if spatial_query
+            .cast_shape(collider, raised, rotation, direction, &config, &filter)
+            .is_some()
+        {
+            continue;
+        }
+
+        // Drop down onto the top of the step
+        let ahead = raised + horizontal.normalize() * distance;
+        let Some(step) = spatial_query.cast_shape(
+            collider,
+            ahead,
+            rotation,
+            Dir3::NEG_Y,
+            &ShapeCastConfig::from_max_distance(max_step_height.0),
+            &filter,
+        ) else {
+            continue;
+        };
+
+        let step_up = max_step_height.0 - step.distance;
+        if step_up > 0.0 {
+            transform.translation.y += step_up as f32;
         }
     }
 }
 
-/// Update camera_follow_player_system to strictly follow player rotation
-fn camera_follow_player_system(
-    player_query: Query<&Transform, With<Player>>,
-    mut camera_query: Query<&mut Transform, (With<FlyCam>, Without<Player>)>,
+/// Slides airborne character controllers down slopes that are too steep to stand on.
+fn slide_on_steep_slopes(
     time: Res<Time>,
+    mut query: Query<
+        (
+            &ShapeHits,
+            &Rotation,
+            &MaxSlopeAngle,
+            &SlopeSlideAcceleration,
+            &mut LinearVelocity,
+        ),
+        (With<CharacterController>, Without<Grounded>),
+    >,
 ) {
-    if let Ok(player_transform) = player_query.single() {
-        for mut camera_transform in camera_query.iter_mut() {
-            let player_pos = player_transform.translation;
-            let camera_distance = 18.0;
-            let camera_height = 4.0;
-            
-            // Get player's forward direction
-            let player_forward = player_transform.forward();
-            
-            // Calculate camera position in front of player (opposite of before)
-            let offset = Vec3::new(
-                player_forward.x * camera_distance,  // Removed negative sign
-                camera_height,
-                player_forward.z * camera_distance,  // Removed negative sign
-            );
-            
-            let target_pos = player_pos + offset;
-            
-            // Smoothly move camera to new position
-            camera_transform.translation = camera_transform.translation.lerp(
-                target_pos,
-                (5.0 * time.delta_secs()).min(1.0),
-            );
-            
-            // Make camera look at player
-            camera_transform.look_at(player_pos, Vec3::Y);
+    let delta_time = time.delta_secs_f64().adjust_precision();
+
+    for (hits, rotation, max_slope_angle, slide_acceleration, mut linear_velocity) in &mut query {
+        let Some(normal) = hits
+            .iter()
+            .map(|hit| rotation * -hit.normal2)
+            .find(|normal| normal.angle_between(Vector::Y) > max_slope_angle.0)
+        else {
+            continue;
+        };
+
+        let downhill = project_on_plane(Vector::NEG_Y, normal).normalize_or_zero();
+        linear_velocity.0 += downhill * slide_acceleration.0 * delta_time;
+    }
+}
+
+/// Performs buffered jumps while grounded or within the coyote time window.
+fn apply_jump(
+    time: Res<Time>,
+    mut controllers: Query<(
+        &JumpImpulse,
+        &StaminaCosts,
+        Option<&mut Stamina>,
+        &mut JumpWindows,
+        &mut LinearVelocity,
+        Has<Grounded>,
+    )>,
+) {
+    for (jump_impulse, stamina_costs, mut stamina, mut jump_windows, mut linear_velocity, is_grounded) in
+        &mut controllers
+    {
+        if !jump_windows.buffered {
+            continue;
+        }
+
+        let can_jump = is_grounded || jump_windows.coyote_remaining > 0.0;
+        if can_jump
+            && stamina
+                .as_mut()
+                .is_none_or(|stamina| stamina.try_spend(stamina_costs.jump))
+        {
+            linear_velocity.y = jump_impulse.0;
+            jump_windows.buffered = false;
+            jump_windows.coyote_remaining = 0.0;
+            jump_windows.lockout_remaining = JumpWindows::LOCKOUT;
+            continue;
+        }
+
+        jump_windows.buffer_remaining -= time.delta_secs();
+        if jump_windows.buffer_remaining <= 0.0 {
+            jump_windows.buffered = false;
+        }
+    }
+}
+
+/// Horizontal speed below which a sprinting character counts as standing still.
+const SPRINT_MIN_SPEED: Scalar = 0.5;
+
+/// Whether a character is actually sprinting rather than just holding the sprint input.
+fn is_sprinting(stance: &Stance, linear_velocity: &LinearVelocity) -> bool {
+    *stance == Stance::Sprint && linear_velocity.xz().length() > SPRINT_MIN_SPEED
+}
+
+/// Drains stamina while sprinting.
+fn drain_sprint_stamina(
+    time: Res<Time>,
+    mut query: Query<(&Stance, &LinearVelocity, &StaminaCosts, &mut Stamina)>,
+) {
+    for (stance, linear_velocity, costs, mut stamina) in &mut query {
+        if is_sprinting(stance, linear_velocity) {
+            stamina.drain(costs.sprint_per_second * time.delta_secs());
         }
     }
-} 
\ No newline at end of file
+}
+
+/// Regenerates stamina over time.
+fn regenerate_stamina(
+    time: Res<Time>,
+    mut query: Query<(&Stance, &LinearVelocity, &mut Stamina)>,
+) {
+    for (stance, linear_velocity, mut stamina) in &mut query {
+        // Only touch stamina that needs it, so change detection stays meaningful
+        if !is_sprinting(stance, linear_velocity)
+            && (stamina.current < stamina.max || stamina.exhausted)
+        {
+            stamina.regenerate(time.delta_secs());
+        }
+    }
+}
+
+/// Sends [`StaminaChanged`] events for the HUD when the shown values change.
+fn report_stamina(
+    mut stamina_event_writer: EventWriter<StaminaChanged>,
+    mut reported: Local<HashMap<Entity, (f32, f32, bool)>>,
+    query: Query<(Entity, &Stamina), Changed<Stamina>>,
+) {
+    for (entity, stamina) in &query {
+        // Regeneration delays touch the component without changing what's shown
+        let values = (stamina.current, stamina.max, stamina.exhausted);
+        if reported.insert(entity, values) == Some(values) {
+            continue;
+        }
+
+        stamina_event_writer.write(StaminaChanged {
+            entity,
+            current: stamina.current,
+            max: stamina.max,
+            exhausted: stamina.exhausted,
+        });
+    }
+}
+
+/// Slows down movement along the ground plane. Gravity is left to the physics engine.
+fn apply_movement_damping(
+    time: Res<Time>,
+    mut query: Query<(&MovementDampingFactor, &GroundNormal, &mut LinearVelocity)>,
+) {
+    // Scale the per 1/60th second damping to the actual timestep
+    let steps = time.delta_secs_f64().adjust_precision() * 60.0;
+
+    for (damping_factor, ground_normal, mut linear_velocity) in &mut query {
+        // The ground normal is straight up while airborne, which damps only the XZ plane
+        let normal = ground_normal.0 * linear_velocity.dot(ground_normal.0);
+        let tangent = linear_velocity.0 - normal;
+        linear_velocity.0 = tangent * damping_factor.0.powf(steps) + normal;
+    }
+}
+
+/// Gap kept between kinematic controllers and the surfaces they touch.
+const SKIN_WIDTH: Scalar = 0.01;
+
+/// How many times a kinematic move may slide along a new surface in one step.
+const MAX_SLIDES: usize = 4;
+
+/// Moves kinematic character controllers with collide-and-slide. The desired
+/// [`LinearVelocity`] is swept through the world with shape casts and sliding
+/// along every surface hit, then replaced with the velocity that covers the
+/// collision free path, which the physics step integrates.
+fn kinematic_collide_and_slide(
+    time: Res<Time>,
+    gravity: Res<Gravity>,
+    spatial_query: SpatialQuery,
+    mut query: Query<
+        (
+            Entity,
+            &Collider,
+            &MaxSlopeAngle,
+            Option<&GravityScale>,
+            &Transform,
+            &mut LinearVelocity,
+        ),
+        With<KinematicController>,
+    >,
+) {
+    let delta_time = time.delta_secs_f64().adjust_precision();
+    if delta_time <= 0.0 {
+        return;
+    }
+
+    for (entity, collider, max_slope_angle, gravity_scale, transform, mut linear_velocity) in
+        &mut query
+    {
+        // Kinematic bodies are not affected by gravity, so apply it here
+        linear_velocity.0 += gravity.0 * gravity_scale.map_or(1.0, |scale| scale.0) * delta_time;
+
+        let start = transform.translation.adjust_precision();
+        let rotation = transform.rotation.adjust_precision();
+        let filter = SpatialQueryFilter::from_excluded_entities([entity]);
+
+        let mut position = start;
+        let mut remaining = linear_velocity.0 * delta_time;
+
+        for _ in 0..MAX_SLIDES {
+            let Ok((direction, distance)) = Dir3::new_and_length(remaining.f32()) else {
+                break;
+            };
+            let distance = distance.adjust_precision();
+
+            let Some(hit) = spatial_query.cast_shape(
+                collider,
+                position,
+                rotation,
+                direction,
+                &ShapeCastConfig::from_max_distance(distance + SKIN_WIDTH),
+                &filter,
+            ) else {
+                position += remaining;
+                break;
+            };
+
+            // Move up to the surface, keeping a small gap
+            let travel = (hit.distance - SKIN_WIDTH).clamp(0.0, distance);
+            let step = remaining.normalize() * travel;
+            position += step;
+            remaining -= step;
+
+            // Slide along the surface. Walkable ground only redirects horizontal
+            // movement, so gravity doesn't drag the character down gentle slopes.
+            remaining = if hit.normal1.angle_between(Vector::Y) <= max_slope_angle.0 {
+                project_on_plane(remaining.with_y(0.0), hit.normal1)
+            } else {
+                project_on_plane(remaining, hit.normal1)
+            };
+        }
+
+        linear_velocity.0 = (position - start) / delta_time;
+    }
+}
@@ -0,0 +1,215 @@
+use avian3d::{interpolation::TranslationEasingState, prelude::*};
+use bevy::prelude::*;
+
+use crate::systems::character_controller::Grounded;
+use crate::{DamageSource, Health, Invulnerable, Player, PlayerDamaged, ShakeCamera};
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<LevelBounds>()
+        .init_resource::<Checkpoint>()
+        .add_event::<PlayerDamaged>()
+        .add_systems(
+            FixedUpdate,
+            (
+                record_spawn_checkpoint,
+                activate_respawn_points,
+                apply_fall_damage,
+                respawn_out_of_bounds,
+            )
+                .chain(),
+        );
+}
+
+/// Anything falling below `min_y` has left the level
+#[derive(Resource)]
+pub struct LevelBounds {
+    pub min_y: f32,
+}
+
+impl Default for LevelBounds {
+    fn default() -> Self {
+        Self { min_y: -100.0 }
+    }
+}
+
+/// Where the player respawns after leaving the level
+#[derive(Resource, Default, Debug)]
+pub struct Checkpoint(pub Option<Vec3>);
+
+/// A sensor volume that sends the player back to the last checkpoint on contact
+#[derive(Component, Default)]
+#[require(Sensor, CollisionEventsEnabled)]
+pub struct KillVolume;
+
+/// Becomes the active checkpoint once the player comes within `radius`
+#[derive(Component)]
+pub struct RespawnPoint {
+    pub radius: f32,
+}
+
+impl Default for RespawnPoint {
+    fn default() -> Self {
+        Self { radius: 5.0 }
+    }
+}
+
+/// Damage taken when landing faster than `safe_speed`
+#[derive(Component)]
+pub struct FallDamage {
+    /// Landing speed that causes no damage
+    pub safe_speed: f32,
+    /// Damage per unit of landing speed above `safe_speed`
+    pub damage_per_speed: f32,
+    /// Fastest downward speed reached since leaving the ground
+    fall_speed: f32,
+}
+
+impl FallDamage {
+    pub fn new(safe_speed: f32, damage_per_speed: f32) -> Self {
+        Self {
+            safe_speed,
+            damage_per_speed,
+            fall_speed: 0.0,
+        }
+    }
+
+    /// The damage for landing at `speed`
+    fn damage(&self, speed: f32) -> u32 {
+        ((speed - self.safe_speed).max(0.0) * self.damage_per_speed).round() as u32
+    }
+}
+
+impl Default for FallDamage {
+    fn default() -> Self {
+        Self::new(30.0, 2.0)
+    }
+}
+
+/// Uses the player's starting position as the first checkpoint.
+fn record_spawn_checkpoint(
+    mut checkpoint: ResMut<Checkpoint>,
+    players: Query<&Transform, Added<Player>>,
+) {
+    for transform in &players {
+        checkpoint.0 = Some(transform.translation);
+    }
+}
+
+/// Activates the respawn points the player passes by.
+fn activate_respawn_points(
+    mut checkpoint: ResMut<Checkpoint>,
+    players: Query<&Transform, With<Player>>,
+    points: Query<(&RespawnPoint, &GlobalTransform)>,
+) {
+    for player in &players {
+        for (point, transform) in &points {
+            let position = transform.translation();
+            if player.translation.distance(position) <= point.radius
+                && checkpoint.0 != Some(position)
+            {
+                info!("Checkpoint reached at {}", position);
+                checkpoint.0 = Some(position);
+            }
+        }
+    }
+}
+
+/// Tracks the fall speed while airborne and converts it into damage on landing.
+fn apply_fall_damage(
+    mut damage_event_writer: EventWriter<PlayerDamaged>,
+    mut shake_event_writer: EventWriter<ShakeCamera>,
+    mut players: Query<
+        (
+            Entity,
+            &mut FallDamage,
+            &mut Health,
+            &LinearVelocity,
+            Has<Grounded>,
+            Has<Invulnerable>,
+        ),
+        With<Player>,
+    >,
+) {
+    for (entity, mut fall_damage, mut health, velocity, is_grounded, is_invulnerable) in
+        &mut players
+    {
+        if !is_grounded {
+            fall_damage.fall_speed = fall_damage.fall_speed.max(-velocity.y);
+            continue;
+        }
+
+        let landing_speed = std::mem::take(&mut fall_damage.fall_speed);
+        if landing_speed > fall_damage.safe_speed {
+            // Hard landings shake the camera, even while invulnerable
+            shake_event_writer.write(ShakeCamera {
+                intensity: (landing_speed / fall_damage.safe_speed - 1.0).min(1.0),
+            });
+        }
+        let amount = fall_damage.damage(landing_speed);
+        if amount == 0 || is_invulnerable {
+            continue;
+        }
+
+        health.current = health.current.saturating_sub(amount);
+        damage_event_writer.write(PlayerDamaged {
+            entity,
+            amount,
+            source: DamageSource::Fall,
+        });
+    }
+}
+
+/// Respawns players that fell out of the level or touched a [`KillVolume`].
+fn respawn_out_of_bounds(
+    mut collision_event_reader: EventReader<CollisionStarted>,
+    bounds: Res<LevelBounds>,
+    checkpoint: Res<Checkpoint>,
+    kill_volumes: Query<(), With<KillVolume>>,
+    mut players: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut LinearVelocity,
+            Option<&mut FallDamage>,
+            Option<&mut TranslationEasingState>,
+        ),
+        With<Player>,
+    >,
+) {
+    let killed: Vec<Entity> = collision_event_reader
+        .read()
+        .filter_map(|CollisionStarted(a, b)| {
+            if kill_volumes.contains(*a) {
+                Some(*b)
+            } else if kill_volumes.contains(*b) {
+                Some(*a)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let Some(respawn_position) = checkpoint.0 else {
+        return;
+    };
+
+    for (entity, mut transform, mut velocity, fall_damage, easing) in &mut players {
+        if transform.translation.y >= bounds.min_y && !killed.contains(&entity) {
+            continue;
+        }
+
+        info!("Player left the level, respawning at {}", respawn_position);
+        transform.translation = respawn_position;
+        velocity.0 = default();
+
+        // Interpolated bodies would otherwise visibly streak to the respawn point
+        if let Some(mut easing) = easing {
+            easing.start = Some(respawn_position);
+        }
+
+        // The fall that led out of the level shouldn't hurt on respawn
+        if let Some(mut fall_damage) = fall_damage {
+            fall_damage.fall_speed = 0.0;
+        }
+    }
+}
@@ -3,36 +3,102 @@ use bevy::window::{MonitorSelection, WindowMode}; // Added MonitorSelection
 use bevy_enhanced_input::prelude::*;
 
 use crate::game::components::PlayerInput;
+use crate::rendering::cameras::camera_mode::CameraMode;
+use crate::rendering::cameras::cinematic::CinematicPlayback;
+use crate::rendering::cameras::minimap::MapView;
+use crate::rendering::cameras::photo_mode::{PhotoMode, TogglePhotoMode};
+use crate::{AttackStarted, GameSettings, Player};
 use crate::screens::Screen;
-use crate::systems::character_controller::CharacterControllerPlugin;
+use crate::screens::settings::SettingsReturn;
+use crate::screens::transition::RequestScreen;
+use crate::systems::character_controller::{CharacterControllerPlugin, MovementAction, StanceRequest};
+use crate::systems::targeting::TargetingAction;
 
 /// Plugin responsible for handling input in the Elysium game
 pub struct ElysiumInputPlugin;
 
 impl Plugin for ElysiumInputPlugin {
     fn build(&self, app: &mut App) {
-        app.add_input_context::<ElysiumInput>()
+        app.add_input_context::<GlobalInput>()
+            .add_input_context::<PhotoModeInput>()
+            .add_input_context::<MenuInput>()
+            .add_input_context::<ElysiumInput>()
+            .add_input_context::<InventoryInput>()
             .add_input_context::<GameCreation>()
+            .add_input_context::<DungeonInput>()
+            .add_input_context::<CinematicInput>()
             .add_systems(Startup, setup_input)
+            .add_systems(
+                Update,
+                sync_input_contexts.run_if(state_changed::<Screen>.or(photo_mode_toggled)),
+            )
+            .add_observer(global_binding)
+            .add_observer(menu_binding)
             .add_observer(binding)
+            .add_observer(inventory_binding)
+            .add_observer(dungeon_binding)
+            .add_observer(cinematic_binding)
+            .add_observer(photo_mode_binding)
+            .add_observer(enable_cinematic_input)
+            .add_observer(disable_cinematic_input)
+            .add_observer(handle_skip_cinematic)
             .add_observer(handle_toggle_fullscreen)
-            .add_observer(handle_return_to_menu)
+            .add_observer(handle_pause)
+            .add_observer(handle_navigate_back)
+            .add_observer(handle_close_inventory)
+            .add_observer(handle_end_turn)
             .add_observer(handle_jump) // New observer for Jump
             .add_observer(handle_sprint) // New observer for Sprint
+            .add_observer(handle_sprint_released)
             .add_observer(handle_crouch) // New observer for Crouch
+            .add_observer(handle_crouch_released)
+            .add_observer(handle_dodge)
             .add_observer(handle_interact) // New observer for Interact
             .add_observer(handle_primary_attack) // New observer for Primary Attack
             .add_observer(handle_inventory) // New observer for Inventory
+            .add_observer(handle_cycle_camera_mode)
+            .add_observer(handle_lock_on)
+            .add_observer(handle_toggle_map)
+            .add_observer(handle_photo_mode)
+            .add_observer(handle_exit_photo_mode)
+            .add_observer(handle_cycle_target)
             .add_observer(pre_gameplay_binding)
-            .add_observer(toggle_game_creation)
             .add_plugins(CharacterControllerPlugin); // Register the avian3d character controller plugin
     }
 }
 
+/// Input context that stays active on every screen
+#[derive(InputContext)]
+pub struct GlobalInput;
+
+/// Input context for navigating menu screens
+#[derive(InputContext)]
+pub struct MenuInput;
+
 /// Input context for the Elysium game
 #[derive(InputContext)]
 pub struct ElysiumInput;
 
+/// Input context for the inventory, which replaces gameplay while it is open
+#[derive(InputContext)]
+#[input_context(priority = 1)]
+pub struct InventoryInput;
+
+/// Input context for the turn-based dungeon encounters
+#[derive(InputContext)]
+#[input_context(priority = 1)]
+pub struct DungeonInput;
+
+/// Input context replacing gameplay while photo mode is active, so the frozen
+/// game can't be played
+#[derive(InputContext)]
+pub struct PhotoModeInput;
+
+/// Input context active while a cinematic plays, above every other context
+#[derive(InputContext)]
+#[input_context(priority = 2)]
+pub struct CinematicInput;
+
 // --- Core Game Actions ---
 
 /// Action for toggling between fullscreen and windowed mode
@@ -40,15 +106,44 @@ pub struct ElysiumInput;
 #[input_action(output = bool)]
 struct ToggleFullScreen;
 
-/// Action for returning to the main menu
+/// Action for pausing the game
+#[derive(Debug, InputAction)]
+// Keys still held from leaving photo mode shouldn't pause
+#[input_action(output = bool, require_reset = true)]
+struct PauseGame;
+
+impl PauseGame {
+    const KEY: KeyCode = KeyCode::Escape;
+}
+
+/// Action for leaving the current menu screen
 #[derive(Debug, InputAction)]
 #[input_action(output = bool)]
-struct ReturnToMainMenu;
+struct NavigateBack;
 
-impl ReturnToMainMenu {
+impl NavigateBack {
     const KEY: KeyCode = KeyCode::Escape;
 }
 
+/// Action for skipping a cinematic, on the same keys that skip the intro movie
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+struct SkipCinematic;
+
+/// Action for closing the inventory
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+struct CloseInventory;
+
+/// Action for ending the current dungeon turn
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+struct EndTurn;
+
+impl EndTurn {
+    const KEY: KeyCode = KeyCode::Enter;
+}
+
 /// Action for movement (WASD, Arrow Keys, Left Stick)
 #[derive(Debug, InputAction)]
 #[input_action(output = Vec2)]
@@ -78,7 +173,16 @@ impl Sprint {
 struct Crouch;
 
 impl Crouch {
-    const KEY: KeyCode = KeyCode::ControlRight; // Left Ctrl for crouch
+    const KEY: KeyCode = KeyCode::ControlLeft; // Left Ctrl for crouch
+}
+
+/// Action for dodging
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+struct Dodge;
+
+impl Dodge {
+    const KEY: KeyCode = KeyCode::KeyQ; // Q for dodge
 }
 
 /// Action for interacting with objects
@@ -109,15 +213,67 @@ impl OpenInventory {
     // Could also use KeyCode::Tab, but I is more explicit for 'Inventory'
 }
 
+/// Action for locking on to an opponent, or releasing the lock
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+struct LockOn;
+
+impl LockOn {
+    const KEY: KeyCode = KeyCode::Tab;
+}
+
+/// Action for switching the lock to another opponent
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+struct CycleTarget;
+
+impl CycleTarget {
+    const KEY: KeyCode = KeyCode::KeyR;
+}
+
+/// Action for switching between the minimap and the full screen map
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+struct ToggleMap;
+
+impl ToggleMap {
+    const KEY: KeyCode = KeyCode::KeyM; // M for map
+}
+
+/// Action for entering photo mode
+#[derive(Debug, InputAction)]
+#[input_action(output = bool, require_reset = true)]
+struct PhotoModeAction;
+
+impl PhotoModeAction {
+    const KEY: KeyCode = KeyCode::KeyP; // P for photo
+}
+
+/// Action for leaving photo mode. Shares keys with entering it, so they have
+/// to be released first.
+#[derive(Debug, InputAction)]
+#[input_action(output = bool, require_reset = true)]
+struct ExitPhotoMode;
+
+/// Action for switching between camera modes
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+struct CycleCameraMode;
+
+impl CycleCameraMode {
+    const KEY: KeyCode = KeyCode::KeyV; // V for view
+}
+
 // --- Setup and Binding Systems ---
 
 fn setup_input(mut commands: Commands) {
-    commands.spawn((PlayerInput, Actions::<ElysiumInput>::default()));
+    // Contexts are attached by `sync_input_contexts` once the screen is known
+    commands.spawn(PlayerInput);
 }
 
-fn binding(
-    trigger: Trigger<Binding<ElysiumInput>>,
-    mut actions: Query<&mut Actions<ElysiumInput>>,
+fn global_binding(
+    trigger: Trigger<Binding<GlobalInput>>,
+    mut actions: Query<&mut Actions<GlobalInput>>,
 ) {
     let mut actions = actions.get_mut(trigger.target()).unwrap();
 
@@ -126,11 +282,28 @@ fn binding(
         .bind::<ToggleFullScreen>()
         .to((KeyCode::F11, (KeyCode::AltLeft, KeyCode::Enter)))
         .with_conditions(Press::default());
+}
+
+fn menu_binding(trigger: Trigger<Binding<MenuInput>>, mut actions: Query<&mut Actions<MenuInput>>) {
+    let mut actions = actions.get_mut(trigger.target()).unwrap();
+
+    // Back (Escape, Gamepad East)
+    actions
+        .bind::<NavigateBack>()
+        .to((NavigateBack::KEY, GamepadButton::East))
+        .with_conditions(Press::default());
+}
+
+fn binding(
+    trigger: Trigger<Binding<ElysiumInput>>,
+    mut actions: Query<&mut Actions<ElysiumInput>>,
+) {
+    let mut actions = actions.get_mut(trigger.target()).unwrap();
 
-    // Return to Main Menu
+    // Pause (Escape, Gamepad Start)
     actions
-        .bind::<ReturnToMainMenu>()
-        .to(ReturnToMainMenu::KEY)
+        .bind::<PauseGame>()
+        .to((PauseGame::KEY, GamepadButton::Start))
         .with_conditions(Press::default());
 
     // Movement (WASD, Arrow Keys, Gamepad Left Stick)
@@ -140,10 +313,10 @@ fn binding(
         Cardinal::arrow_keys(),
     ));
 
-    // Jump (Spacebar)
+    // Jump (Spacebar, Gamepad South)
     actions
         .bind::<Jump>()
-        .to(Jump::KEY)
+        .to((Jump::KEY, GamepadButton::South))
         .with_conditions(Press::default());
 
     // Sprint (Left Shift)
@@ -158,6 +331,12 @@ fn binding(
         .to(Crouch::KEY)
         .with_conditions(Hold::new(0.0)); // Use Hold for continuous crouch
 
+    // Dodge (Q Key, Gamepad East)
+    actions
+        .bind::<Dodge>()
+        .to((Dodge::KEY, GamepadButton::East))
+        .with_conditions(Press::default());
+
     // Interact (E Key)
     actions
         .bind::<Interact>()
@@ -176,10 +355,88 @@ fn binding(
         .to(OpenInventory::KEY)
         .with_conditions(Press::default());
 
-    // Existing: Toggle Game Creation Mode (N Key)
-    // This binding is also present in the GameCreation context, which might be fine if you want to toggle it from both.
-    // If 'N' is ONLY for toggling GameCreation, consider if it should only be bound in one context.
-    actions.bind::<ToggleGameCreationMode>().to(KeyCode::KeyN);
+    // Lock On (Tab, Middle Mouse, Gamepad Right Stick Press)
+    actions
+        .bind::<LockOn>()
+        .to((LockOn::KEY, MouseButton::Middle, GamepadButton::RightThumb))
+        .with_conditions(Press::default());
+
+    // Cycle Target (R Key, Gamepad D-Pad Right)
+    actions
+        .bind::<CycleTarget>()
+        .to((CycleTarget::KEY, GamepadButton::DPadRight))
+        .with_conditions(Press::default());
+
+    // Toggle Map (M Key, Gamepad D-Pad Up)
+    actions
+        .bind::<ToggleMap>()
+        .to((ToggleMap::KEY, GamepadButton::DPadUp))
+        .with_conditions(Press::default());
+
+    // Photo Mode (P Key)
+    actions
+        .bind::<PhotoModeAction>()
+        .to(PhotoModeAction::KEY)
+        .with_conditions(Press::default());
+
+    // Cycle Camera Mode (V Key, Gamepad Select)
+    actions
+        .bind::<CycleCameraMode>()
+        .to((CycleCameraMode::KEY, GamepadButton::Select))
+        .with_conditions(Press::default());
+}
+
+fn inventory_binding(
+    trigger: Trigger<Binding<InventoryInput>>,
+    mut actions: Query<&mut Actions<InventoryInput>>,
+) {
+    let mut actions = actions.get_mut(trigger.target()).unwrap();
+
+    // Close Inventory (Escape or I Key). Both keys are consumed here, so the
+    // gameplay context underneath never sees them while the inventory is open.
+    actions
+        .bind::<CloseInventory>()
+        .to((KeyCode::Escape, OpenInventory::KEY))
+        .with_conditions(Press::default());
+}
+
+fn cinematic_binding(
+    trigger: Trigger<Binding<CinematicInput>>,
+    mut actions: Query<&mut Actions<CinematicInput>>,
+) {
+    let mut actions = actions.get_mut(trigger.target()).unwrap();
+
+    // Skip (Escape, Gamepad East), consumed so the gameplay context never sees it
+    actions
+        .bind::<SkipCinematic>()
+        .to((NavigateBack::KEY, GamepadButton::East))
+        .with_conditions(Press::default());
+}
+
+fn photo_mode_binding(
+    trigger: Trigger<Binding<PhotoModeInput>>,
+    mut actions: Query<&mut Actions<PhotoModeInput>>,
+) {
+    let mut actions = actions.get_mut(trigger.target()).unwrap();
+
+    // Leave (P, Escape, Gamepad East). The camera controls read raw input.
+    actions
+        .bind::<ExitPhotoMode>()
+        .to((PhotoModeAction::KEY, PauseGame::KEY, GamepadButton::East))
+        .with_conditions(Press::default());
+}
+
+fn dungeon_binding(
+    trigger: Trigger<Binding<DungeonInput>>,
+    mut actions: Query<&mut Actions<DungeonInput>>,
+) {
+    let mut actions = actions.get_mut(trigger.target()).unwrap();
+
+    // End Turn (Enter)
+    actions
+        .bind::<EndTurn>()
+        .to(EndTurn::KEY)
+        .with_conditions(Press::default());
 }
 
 // --- Action Handling Systems ---
@@ -206,13 +463,89 @@ fn handle_toggle_fullscreen(
     }
 }
 
-fn handle_return_to_menu(
-    trigger: Trigger<Started<ReturnToMainMenu>>,
-    mut next_state: ResMut<NextState<Screen>>,
+fn handle_pause(trigger: Trigger<Started<PauseGame>>, mut requests: EventWriter<RequestScreen>) {
+    if trigger.value {
+        info!("Pausing game");
+        requests.write(RequestScreen(Screen::Pause));
+    }
+}
+
+fn handle_navigate_back(
+    trigger: Trigger<Started<NavigateBack>>,
+    mut requests: EventWriter<RequestScreen>,
+    current_screen_state: Res<State<Screen>>,
+    settings_return: Res<SettingsReturn>,
+) {
+    if trigger.value {
+        match current_screen_state.get() {
+            Screen::Intro | Screen::NewGame | Screen::GameOver => {
+                info!("Navigating back to main menu");
+                requests.write(RequestScreen(Screen::MainMenu));
+            }
+            Screen::Settings => {
+                info!("Navigating back to {:?}", settings_return.0);
+                requests.write(RequestScreen(settings_return.0.clone()));
+            }
+            Screen::Pause => {
+                info!("Resuming game");
+                requests.write(RequestScreen(Screen::GamePlay));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn handle_close_inventory(
+    trigger: Trigger<Started<CloseInventory>>,
+    mut requests: EventWriter<RequestScreen>,
+) {
+    if trigger.value {
+        info!("Closing Inventory");
+        requests.write(RequestScreen(Screen::GamePlay));
+    }
+}
+
+fn handle_end_turn(trigger: Trigger<Started<EndTurn>>) {
+    if trigger.value {
+        info!("Player ended the turn");
+    }
+}
+
+/// Attaches the cinematic context while any camera plays a cinematic
+fn enable_cinematic_input(
+    _trigger: Trigger<OnAdd, CinematicPlayback>,
+    mut commands: Commands,
+    inputs: Query<Entity, With<PlayerInput>>,
+) {
+    for entity in &inputs {
+        set_context::<CinematicInput>(&mut commands.entity(entity), true);
+    }
+}
+
+fn disable_cinematic_input(
+    _trigger: Trigger<OnRemove, CinematicPlayback>,
+    mut commands: Commands,
+    inputs: Query<Entity, With<PlayerInput>>,
+    playbacks: Query<(), With<CinematicPlayback>>,
+) {
+    // The removed playback is still counted while the observer runs
+    if playbacks.iter().count() > 1 {
+        return;
+    }
+    for entity in &inputs {
+        set_context::<CinematicInput>(&mut commands.entity(entity), false);
+    }
+}
+
+fn handle_skip_cinematic(
+    trigger: Trigger<Started<SkipCinematic>>,
+    mut playbacks: Query<&mut CinematicPlayback>,
 ) {
     if trigger.value {
-        info!("Returning to main menu");
-        next_state.set(Screen::MainMenu);
+        info!("Skipping cinematic");
+        for mut playback in &mut playbacks {
+            playback.skip();
+        }
     }
 }
 
@@ -220,30 +553,71 @@ fn handle_return_to_menu(
 
 fn handle_jump(
     trigger: Trigger<Started<Jump>>,
-    // mut players: Query<&mut Transform, With<Player>>,
+    camera_mode: Res<CameraMode>,
+    mut movement_event_writer: EventWriter<MovementAction>,
 ) {
-    if trigger.value {
-        info!("Player jumped!");
+    // The free camera flies with the movement keys instead
+    if trigger.value && *camera_mode != CameraMode::FreeDebug {
+        movement_event_writer.write(MovementAction::Jump);
     }
 }
 
-fn handle_sprint(trigger: Trigger<Fired<Sprint>>) {
-    if trigger.value {
-        info!("Player is sprinting...");
+fn handle_sprint(
+    trigger: Trigger<Fired<Sprint>>,
+    camera_mode: Res<CameraMode>,
+    mut requests: Query<&mut StanceRequest>,
+) {
+    // Shift boosts the free camera instead
+    if trigger.value && *camera_mode != CameraMode::FreeDebug {
+        for mut request in &mut requests {
+            request.sprint = true;
+        }
+    }
+}
+
+fn handle_sprint_released(
+    _trigger: Trigger<Completed<Sprint>>,
+    mut requests: Query<&mut StanceRequest>,
+) {
+    for mut request in &mut requests {
+        request.sprint = false;
     }
 }
 
-fn handle_crouch(trigger: Trigger<Fired<Sprint>>) {
+fn handle_crouch(trigger: Trigger<Fired<Crouch>>, mut requests: Query<&mut StanceRequest>) {
     if trigger.value {
-        info!("Player is crouching...");
+        for mut request in &mut requests {
+            request.crouch = true;
+        }
+    }
+}
+
+fn handle_crouch_released(
+    _trigger: Trigger<Completed<Crouch>>,
+    mut requests: Query<&mut StanceRequest>,
+) {
+    for mut request in &mut requests {
+        request.crouch = false;
+    }
+}
+
+fn handle_dodge(
+    trigger: Trigger<Started<Dodge>>,
+    camera_mode: Res<CameraMode>,
+    mut movement_event_writer: EventWriter<MovementAction>,
+) {
+    if trigger.value && *camera_mode != CameraMode::FreeDebug {
+        movement_event_writer.write(MovementAction::Dodge);
     }
 }
 
 fn handle_interact(
     trigger: Trigger<Started<Interact>>,
+    camera_mode: Res<CameraMode>,
     // You'd query for nearby interactable objects here
 ) {
-    if trigger.value {
+    // E flies the free camera up instead
+    if trigger.value && *camera_mode != CameraMode::FreeDebug {
         info!("Player interacted!");
         // Logic to check for nearby interactable objects and activate them
     }
@@ -251,37 +625,85 @@ fn handle_interact(
 
 fn handle_primary_attack(
     trigger: Trigger<Started<PrimaryAttack>>,
-    // Query for player's weapon component or attack logic
+    players: Query<Entity, With<Player>>,
+    mut attack_event_writer: EventWriter<AttackStarted>,
 ) {
     if trigger.value {
-        info!("Player attacked!");
-        // Logic for shooting, melee attack, etc.
+        for entity in &players {
+            info!("Player attacked!");
+            attack_event_writer.write(AttackStarted { entity });
+        }
     }
 }
 
 fn handle_inventory(
     trigger: Trigger<Started<OpenInventory>>,
-    mut next_state: ResMut<NextState<Screen>>,
-    current_screen_state: Res<State<Screen>>, // Get current screen state
+    mut requests: EventWriter<RequestScreen>,
 ) {
+    // Closing is handled by the `InventoryInput` context that replaces this one
     if trigger.value {
-        info!("Toggling Inventory");
-        // Example: Toggle between InGame and Inventory screens
-        if *current_screen_state.get() == Screen::GamePlay {
-            next_state.set(Screen::Inventory); // Assuming you have an Inventory screen
-        } else if *current_screen_state.get() == Screen::Inventory {
-            next_state.set(Screen::GamePlay);
-        }
+        info!("Opening Inventory");
+        requests.write(RequestScreen(Screen::Inventory));
     }
 }
 
-// --- Game Creation Specific Input ---
+fn handle_photo_mode(
+    trigger: Trigger<Started<PhotoModeAction>>,
+    mut photo_mode_event_writer: EventWriter<TogglePhotoMode>,
+) {
+    if trigger.value {
+        photo_mode_event_writer.write(TogglePhotoMode);
+    }
+}
 
-#[derive(Debug, InputAction)]
-#[input_action(output = bool, require_reset = true)]
-struct ToggleGameCreationMode;
+fn handle_exit_photo_mode(
+    trigger: Trigger<Started<ExitPhotoMode>>,
+    mut photo_mode_event_writer: EventWriter<TogglePhotoMode>,
+) {
+    if trigger.value {
+        photo_mode_event_writer.write(TogglePhotoMode);
+    }
+}
+
+fn handle_cycle_camera_mode(
+    trigger: Trigger<Started<CycleCameraMode>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    // The settings are the one source of truth, `sync_camera_mode` applies the change
+    if trigger.value {
+        settings.camera_mode = settings.camera_mode.next();
+        info!("Camera mode: {:?}", settings.camera_mode);
+    }
+}
+
+fn handle_toggle_map(trigger: Trigger<Started<ToggleMap>>, mut map_view: ResMut<MapView>) {
+    if trigger.value {
+        *map_view = map_view.toggled();
+    }
+}
+
+fn handle_lock_on(
+    trigger: Trigger<Started<LockOn>>,
+    mut targeting_event_writer: EventWriter<TargetingAction>,
+) {
+    if trigger.value {
+        targeting_event_writer.write(TargetingAction::ToggleLockOn);
+    }
+}
+
+fn handle_cycle_target(
+    trigger: Trigger<Started<CycleTarget>>,
+    mut targeting_event_writer: EventWriter<TargetingAction>,
+) {
+    if trigger.value {
+        targeting_event_writer.write(TargetingAction::CycleTarget);
+    }
+}
+
+// --- Game Creation Specific Input ---
 
 #[derive(InputContext)]
+#[input_context(priority = 1)]
 pub struct GameCreation;
 
 #[derive(Debug, InputAction)]
@@ -294,34 +716,95 @@ fn pre_gameplay_binding(
 ) {
     let mut actions = actions.get_mut(trigger.target()).unwrap();
     actions.bind::<StartGame>().to(KeyCode::Space);
-    actions.bind::<ToggleGameCreationMode>().to(KeyCode::KeyN);
 }
 
-fn toggle_game_creation(
-    trigger: Trigger<Started<ToggleGameCreationMode>>,
+// --- Input Context Stack ---
+
+/// A layer of input that can be stacked with others. Each layer maps to one
+/// input context, and layers with a higher context priority consume their
+/// bindings before the layers underneath see them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputLayer {
+    Global,
+    Menu,
+    Gameplay,
+    Inventory,
+    GameCreation,
+    Dungeon,
+    PhotoMode,
+}
+
+impl InputLayer {
+    /// The layers that should be active on the given screen
+    pub fn for_screen(screen: &Screen) -> &'static [InputLayer] {
+        use InputLayer::*;
+        match screen {
+            Screen::Intro | Screen::MainMenu | Screen::Settings => &[Global, Menu],
+            Screen::NewGame => &[Global, Menu, GameCreation],
+            Screen::GamePlay => &[Global, Gameplay],
+            // The inventory is modal, so the player can't move or attack behind it
+            Screen::Inventory => &[Global, Inventory],
+            Screen::Dungeon => &[Global, Dungeon],
+            Screen::Pause | Screen::GameOver => &[Global, Menu],
+            Screen::GeneratingLevel => &[Global],
+        }
+    }
+
+    /// The layers for the given screen, with photo mode taking over gameplay while active
+    pub fn active(screen: &Screen, photo_mode: bool) -> Vec<InputLayer> {
+        InputLayer::for_screen(screen)
+            .iter()
+            .map(|layer| match layer {
+                InputLayer::Gameplay if photo_mode => InputLayer::PhotoMode,
+                layer => *layer,
+            })
+            .collect()
+    }
+}
+
+/// Whether photo mode was entered or left since the last check
+fn photo_mode_toggled(photo_mode: Res<PhotoMode>, mut was_active: Local<bool>) -> bool {
+    let toggled = photo_mode.active != *was_active;
+    *was_active = photo_mode.active;
+    toggled
+}
+
+/// Attaches the input contexts for the current screen to every input entity
+/// and removes the ones that are no longer needed.
+fn sync_input_contexts(
     mut commands: Commands,
-    elysium_query: Query<(), With<Actions<ElysiumInput>>>,
-    game_creation_query: Query<(), With<Actions<GameCreation>>>,
+    screen: Res<State<Screen>>,
+    photo_mode: Res<PhotoMode>,
+    inputs: Query<Entity, With<PlayerInput>>,
+    mut stance_requests: Query<&mut StanceRequest>,
 ) {
-    let has_elysium_input = elysium_query.contains(trigger.target());
-    let has_game_creation = game_creation_query.contains(trigger.target());
-
-    if has_elysium_input {
-        info!("Switching to game creation mode");
-        commands
-            .entity(trigger.target())
-            .remove::<Actions<ElysiumInput>>()
-            .insert(Actions::<GameCreation>::default());
-    } else if has_game_creation {
-        info!("Exiting game creation mode");
-        commands
-            .entity(trigger.target())
-            .remove::<Actions<GameCreation>>()
-            .insert(Actions::<ElysiumInput>::default());
+    let layers = InputLayer::active(screen.get(), photo_mode.active);
+    info!("Activating input layers {:?} for {:?}", layers, screen.get());
+
+    // Removing the gameplay context drops held actions without completing them
+    if !layers.contains(&InputLayer::Gameplay) {
+        for mut request in &mut stance_requests {
+            *request = StanceRequest::default();
+        }
+    }
+
+    for entity in &inputs {
+        let mut entity = commands.entity(entity);
+        set_context::<GlobalInput>(&mut entity, layers.contains(&InputLayer::Global));
+        set_context::<MenuInput>(&mut entity, layers.contains(&InputLayer::Menu));
+        set_context::<ElysiumInput>(&mut entity, layers.contains(&InputLayer::Gameplay));
+        set_context::<InventoryInput>(&mut entity, layers.contains(&InputLayer::Inventory));
+        set_context::<GameCreation>(&mut entity, layers.contains(&InputLayer::GameCreation));
+        set_context::<DungeonInput>(&mut entity, layers.contains(&InputLayer::Dungeon));
+        set_context::<PhotoModeInput>(&mut entity, layers.contains(&InputLayer::PhotoMode));
+    }
+}
+
+fn set_context<C: InputContext>(entity: &mut EntityCommands, active: bool) {
+    if active {
+        // Keep already active contexts so their action state is not reset
+        entity.insert_if_new(Actions::<C>::default());
     } else {
-        info!("Initializing default input mode");
-        commands
-            .entity(trigger.target())
-            .insert(Actions::<ElysiumInput>::default());
+        entity.remove::<Actions<C>>();
     }
 }
@@ -1,10 +1,20 @@
+pub mod animation;
 pub mod audio;
+pub mod hazards;
 pub mod input;
 pub mod movie;
+pub mod platforms;
+pub mod targeting;
 pub mod character_controller;
 
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((audio::plugin,));
+    app.add_plugins((
+        animation::plugin,
+        audio::plugin,
+        hazards::plugin,
+        platforms::plugin,
+        targeting::plugin,
+    ));
 }
@@ -0,0 +1,152 @@
+use avian3d::{math::*, prelude::*};
+use bevy::prelude::*;
+
+use super::character_controller::CharacterControllerSystems;
+
+pub fn plugin(app: &mut App) {
+    // Riders are carried by the platform velocity of the same step
+    app.add_systems(
+        FixedUpdate,
+        MovingPlatform::drive.before(CharacterControllerSystems),
+    );
+}
+
+/// How a [`MovingPlatform`] continues after reaching its last waypoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlatformLoop {
+    /// Travel back through the waypoints in reverse
+    #[default]
+    PingPong,
+    /// Continue from the last waypoint straight to the first
+    Cycle,
+}
+
+/// Drives a kinematic body along a path of waypoints. Character controllers
+/// standing on it ride along.
+#[derive(Component)]
+#[require(RigidBody = RigidBody::Kinematic)]
+pub struct MovingPlatform {
+    pub waypoints: Vec<Vec3>,
+    /// Seconds to travel between two waypoints
+    pub travel_time: f32,
+    /// Seconds to wait at each waypoint
+    pub wait_time: f32,
+    pub easing: EaseFunction,
+    pub looping: PlatformLoop,
+    /// Yaw rotation speed in radians per second
+    pub spin: f32,
+    segment: usize,
+    reversed: bool,
+    elapsed: f32,
+}
+
+impl MovingPlatform {
+    pub fn new(waypoints: Vec<Vec3>, travel_time: f32) -> Self {
+        Self {
+            waypoints,
+            travel_time,
+            wait_time: 0.0,
+            easing: EaseFunction::SmoothStep,
+            looping: PlatformLoop::default(),
+            spin: 0.0,
+            segment: 0,
+            reversed: false,
+            elapsed: 0.0,
+        }
+    }
+
+    /// An elevator moving between a bottom and a top position
+    pub fn elevator(bottom: Vec3, top: Vec3, travel_time: f32, wait_time: f32) -> Self {
+        Self::new(vec![bottom, top], travel_time).with_wait_time(wait_time)
+    }
+
+    pub fn with_wait_time(mut self, wait_time: f32) -> Self {
+        self.wait_time = wait_time;
+        self
+    }
+
+    pub fn with_easing(mut self, easing: EaseFunction) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn with_looping(mut self, looping: PlatformLoop) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    pub fn with_spin(mut self, spin: f32) -> Self {
+        self.spin = spin;
+        self
+    }
+
+    /// The waypoints the current segment travels between
+    fn endpoints(&self) -> (Vec3, Vec3) {
+        let count = self.waypoints.len();
+        let from = self.segment % count;
+        let to = match (self.looping, self.reversed) {
+            (PlatformLoop::Cycle, _) => (from + 1) % count,
+            (PlatformLoop::PingPong, false) => (from + 1).min(count - 1),
+            (PlatformLoop::PingPong, true) => from.saturating_sub(1),
+        };
+        (self.waypoints[from], self.waypoints[to])
+    }
+
+    /// Moves on to the next segment once the current one is finished
+    fn advance(&mut self) {
+        let count = self.waypoints.len();
+        match (self.looping, self.reversed) {
+            (PlatformLoop::Cycle, _) => self.segment = (self.segment + 1) % count,
+            (PlatformLoop::PingPong, false) => {
+                self.segment += 1;
+                if self.segment >= count - 1 {
+                    self.reversed = true;
+                }
+            }
+            (PlatformLoop::PingPong, true) => {
+                self.segment = self.segment.saturating_sub(1);
+                if self.segment == 0 {
+                    self.reversed = false;
+                }
+            }
+        }
+    }
+
+    /// Sets the velocity that brings each platform to its next eased position.
+    fn drive(
+        time: Res<Time>,
+        mut platforms: Query<(
+            &mut MovingPlatform,
+            &Transform,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+        )>,
+    ) {
+        let delta = time.delta_secs();
+        if delta <= 0.0 {
+            return;
+        }
+
+        for (mut platform, transform, mut linear_velocity, mut angular_velocity) in &mut platforms {
+            angular_velocity.0 = Vector::Y * platform.spin.adjust_precision();
+
+            if platform.waypoints.len() < 2 || platform.travel_time <= 0.0 {
+                linear_velocity.0 = Vector::ZERO;
+                continue;
+            }
+
+            platform.elapsed += delta;
+            if platform.elapsed >= platform.travel_time + platform.wait_time {
+                platform.elapsed -= platform.travel_time + platform.wait_time;
+                platform.advance();
+            }
+
+            let (from, to) = platform.endpoints();
+            let progress = (platform.elapsed / platform.travel_time).min(1.0);
+            let target = from.lerp(to, platform.easing.sample_clamped(progress));
+
+            // The physics step integrates this velocity to land exactly on the target
+            linear_velocity.0 = ((target - transform.translation) / delta).adjust_precision();
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::rendering::cameras::player_camera::FlyCam;
+use crate::systems::character_controller::facing_rotation;
+use crate::ui::styles::ElysiumDescentColorPalette;
+use crate::{AttackStarted, Opponent, Player};
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<LockOnSettings>()
+        .add_event::<TargetingAction>()
+        .add_event::<AttackStarted>()
+        .add_systems(
+            Update,
+            (
+                handle_targeting_actions,
+                release_lost_targets,
+                aim_attacks,
+                draw_target_marker,
+            )
+                .chain(),
+        );
+}
+
+/// An event sent for a lock-on input action.
+#[derive(Event, Debug, Clone, Copy)]
+pub enum TargetingAction {
+    /// Lock on to the best target, or release the current one
+    ToggleLockOn,
+    /// Switch to the next visible target, left to right on screen
+    CycleTarget,
+}
+
+/// The [`Opponent`] the player is locked on to
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockOnTarget(pub Entity);
+
+/// Which opponents can be locked on to
+#[derive(Resource)]
+pub struct LockOnSettings {
+    /// Furthest distance from the player a target can be picked at
+    pub range: f32,
+    /// Distance at which an existing lock is released
+    pub break_range: f32,
+    /// Largest angle between the camera's forward and a target
+    pub max_angle: f32,
+}
+
+impl Default for LockOnSettings {
+    fn default() -> Self {
+        Self {
+            range: 30.0,
+            break_range: 40.0,
+            max_angle: 60.0_f32.to_radians(),
+        }
+    }
+}
+
+/// Opponents in range, in front of the camera and not hidden behind level geometry
+fn visible_targets(
+    spatial_query: &SpatialQuery,
+    settings: &LockOnSettings,
+    camera: &GlobalTransform,
+    player: (Entity, Vec3),
+    opponents: &Query<(Entity, &GlobalTransform), With<Opponent>>,
+    colliders: &Query<&ColliderOf>,
+) -> Vec<(Entity, Vec3)> {
+    let (player, player_pos) = player;
+    let origin = camera.translation();
+    let forward = camera.forward();
+    let filter = SpatialQueryFilter::from_excluded_entities([player]);
+
+    opponents
+        .iter()
+        .map(|(entity, transform)| (entity, transform.translation()))
+        .filter(|(_, position)| position.distance(player_pos) <= settings.range)
+        .filter(|(entity, position)| {
+            let Ok((direction, distance)) = Dir3::new_and_length(position - origin) else {
+                return false;
+            };
+            if direction.angle_between(*forward) > settings.max_angle {
+                return false;
+            }
+            // The first thing the line of sight hits has to be the opponent itself
+            spatial_query
+                .cast_ray(origin, direction, distance, true, &filter)
+                .is_none_or(|hit| {
+                    hit.entity == *entity
+                        || colliders.get(hit.entity).is_ok_and(|collider| collider.body == *entity)
+                })
+        })
+        .collect()
+}
+
+/// Locks on, releases and cycles targets when the player asks to.
+#[allow(clippy::too_many_arguments)]
+fn handle_targeting_actions(
+    mut commands: Commands,
+    mut action_reader: EventReader<TargetingAction>,
+    spatial_query: SpatialQuery,
+    settings: Res<LockOnSettings>,
+    cameras: Query<&GlobalTransform, With<FlyCam>>,
+    players: Query<(Entity, &GlobalTransform, Option<&LockOnTarget>), With<Player>>,
+    opponents: Query<(Entity, &GlobalTransform), With<Opponent>>,
+    colliders: Query<&ColliderOf>,
+) {
+    let (Ok(camera), Ok((player, player_transform, current))) = (cameras.single(), players.single())
+    else {
+        return;
+    };
+    let player_pos = player_transform.translation();
+
+    for action in action_reader.read() {
+        if matches!(action, TargetingAction::ToggleLockOn) && current.is_some() {
+            commands.entity(player).remove::<LockOnTarget>();
+            continue;
+        }
+
+        let mut targets = visible_targets(
+            &spatial_query,
+            &settings,
+            camera,
+            (player, player_pos),
+            &opponents,
+            &colliders,
+        );
+
+        let target = match (action, current) {
+            (TargetingAction::CycleTarget, Some(current)) => {
+                // Order targets from left to right as seen from the camera
+                let right = camera.right();
+                targets.sort_by(|(_, a), (_, b)| a.dot(*right).total_cmp(&b.dot(*right)));
+                let index = targets.iter().position(|(entity, _)| *entity == current.0);
+                index
+                    .map(|index| targets[(index + 1) % targets.len()])
+                    .or(targets.first().copied())
+            }
+            // Cycling without a lock behaves like locking on
+            _ => targets.into_iter().min_by(|(_, a), (_, b)| {
+                a.distance_squared(player_pos).total_cmp(&b.distance_squared(player_pos))
+            }),
+        };
+
+        if let Some((target, _)) = target {
+            commands.entity(player).insert(LockOnTarget(target));
+        }
+    }
+}
+
+/// Releases the lock when the target is gone or too far away.
+fn release_lost_targets(
+    mut commands: Commands,
+    settings: Res<LockOnSettings>,
+    players: Query<(Entity, &GlobalTransform, &LockOnTarget), With<Player>>,
+    opponents: Query<&GlobalTransform, With<Opponent>>,
+) {
+    for (player, transform, target) in &players {
+        let in_range = opponents.get(target.0).is_ok_and(|target| {
+            target.translation().distance(transform.translation()) <= settings.break_range
+        });
+        if !in_range {
+            commands.entity(player).remove::<LockOnTarget>();
+        }
+    }
+}
+
+/// Turns attackers towards their locked target as the attack starts.
+fn aim_attacks(
+    mut attack_reader: EventReader<AttackStarted>,
+    mut attackers: Query<(&mut Transform, &LockOnTarget)>,
+    targets: Query<&GlobalTransform>,
+) {
+    for attack in attack_reader.read() {
+        let Ok((mut transform, target)) = attackers.get_mut(attack.entity) else {
+            continue;
+        };
+        let Ok(target) = targets.get(target.0) else {
+            continue;
+        };
+        let direction = (target.translation() - transform.translation).with_y(0.0);
+        if direction.length_squared() > f32::EPSILON {
+            transform.rotation = facing_rotation(direction);
+        }
+    }
+}
+
+/// Draws a spinning marker above the locked target.
+fn draw_target_marker(
+    time: Res<Time>,
+    mut gizmos: Gizmos,
+    players: Query<&LockOnTarget, With<Player>>,
+    targets: Query<&GlobalTransform>,
+) {
+    for target in &players {
+        let Ok(transform) = targets.get(target.0) else {
+            continue;
+        };
+        let position = transform.translation() + Vec3::Y * 3.0;
+        let spin = Quat::from_rotation_y(time.elapsed_secs() * 2.0);
+        let pulse = 0.6 + 0.1 * (time.elapsed_secs() * 6.0).sin();
+
+        gizmos.circle(
+            Isometry3d::new(position, spin * Quat::from_rotation_arc(Vec3::Z, Vec3::Y)),
+            pulse,
+            Color::ELYSIUM_DESCENT_RED,
+        );
+        // Downward pointing chevron
+        for side in [-1.0, 1.0] {
+            let tip = position - Vec3::Y * 0.4;
+            let end = position + spin * Vec3::new(side * pulse * 0.5, 0.2, 0.0);
+            gizmos.line(tip, end, Color::ELYSIUM_DESCENT_YELLOW);
+        }
+    }
+}